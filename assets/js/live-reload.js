@@ -0,0 +1,17 @@
+(() => {
+  // Only included by `hot-reload` builds, which push an event after re-reading content/.
+  const source = new EventSource('/__livereload');
+  let disconnected = false;
+
+  source.addEventListener('message', () => window.location.reload());
+  source.addEventListener('error', () => {
+    disconnected = true;
+  });
+  // A reconnect means the server restarted (cargo watch after a Rust change), so the page
+  // may be stale as well.
+  source.addEventListener('open', () => {
+    if (disconnected) {
+      window.location.reload();
+    }
+  });
+})();
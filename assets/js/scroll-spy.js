@@ -9,7 +9,11 @@
   const ACTIVATION_OFFSET = 96;
   const SETTLE_DELAY = 150;
 
+  // Table of contents links (sidebar and the collapsible one on narrow screens).
+  const tocLinks = Array.from(document.querySelectorAll('[data-toc] a[href^="#"]'));
+
   let written = window.location.hash.slice(1);
+  let highlighted = null;
   let frame = 0;
   let timer = 0;
 
@@ -28,6 +32,20 @@
     return active;
   };
 
+  const highlight = (id) => {
+    if (id === highlighted) {
+      return;
+    }
+    highlighted = id;
+    for (const link of tocLinks) {
+      if (link.getAttribute('href') === '#' + id) {
+        link.setAttribute('aria-current', 'location');
+      } else {
+        link.removeAttribute('aria-current');
+      }
+    }
+  };
+
   const commit = () => {
     const heading = activeHeading();
     const id = heading ? heading.id : '';
@@ -45,6 +63,8 @@
     }
     frame = window.requestAnimationFrame(() => {
       frame = 0;
+      const heading = activeHeading();
+      highlight(heading ? heading.id : '');
       // Only rewrite the URL once scrolling settles: smooth-scrolled anchor jumps sweep
       // past many headings, and committing each one churns history.replaceState.
       window.clearTimeout(timer);
@@ -57,6 +77,8 @@
   // touching the URL, otherwise the first update would see scrollY 0 and drop the hash.
   window.addEventListener('load', () => {
     window.requestAnimationFrame(() => {
+      const heading = activeHeading();
+      highlight(heading ? heading.id : '');
       window.addEventListener('scroll', onScroll, { passive: true });
       window.addEventListener('resize', onScroll, { passive: true });
     });
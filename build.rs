@@ -1,6 +1,7 @@
+use sha2::{Digest, Sha256};
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 fn tailwind_binary_name(os: &str, arch: &str) -> Option<&'static str> {
@@ -53,6 +54,47 @@ fn copy_dir_recursive(src: &PathBuf, dst: &PathBuf) {
     }
 }
 
+/// Root-relative URL paths of every file under `dir`, e.g. `/js/code-copy.js` for `assets/js`.
+fn collect_url_paths(root: &Path, dir: &Path, paths: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries {
+        let path = entry
+            .unwrap_or_else(|err| panic!("failed to read directory {}: {}", dir.display(), err))
+            .path();
+        if path.is_dir() {
+            collect_url_paths(root, &path, paths);
+        } else {
+            let relative = path.strip_prefix(root).unwrap().to_string_lossy();
+            paths.push(format!("/{}", relative.replace('\\', "/")));
+        }
+    }
+}
+
+/// Writes `asset_hashes.rs` to `OUT_DIR`: a `(url path, hash)` slice over the files this script
+/// put in `build/`, which `common::assets` appends to their URLs. Post images are copied by the
+/// `Post` derive after this script runs, so they aren't part of it.
+fn write_asset_hashes(build_dir: &Path, paths: &[String]) {
+    let mut manifest = String::from("&[\n");
+    for path in paths {
+        let file = build_dir.join(path.trim_start_matches('/'));
+        let bytes = fs::read(&file)
+            .unwrap_or_else(|err| panic!("failed to read {}: {}", file.display(), err));
+        let hash = Sha256::digest(&bytes)
+            .iter()
+            .take(8)
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>();
+        manifest.push_str(&format!("    ({:?}, {:?}),\n", path, hash));
+    }
+    manifest.push(']');
+
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap()).join("asset_hashes.rs");
+    fs::write(&out, manifest)
+        .unwrap_or_else(|err| panic!("failed to write {}: {}", out.display(), err));
+}
+
 fn main() {
     println!("cargo:rerun-if-changed=styles/index.css");
     println!("cargo:rerun-if-changed=styles/tailwind.config.js");
@@ -108,4 +150,13 @@ fn main() {
     let assets_dir = manifest_dir.join("assets");
     let build_assets_dir = manifest_dir.join("build");
     copy_dir_recursive(&assets_dir, &build_assets_dir);
+
+    let mut built = vec!["/style/index.css".to_string()];
+    collect_url_paths(&assets_dir, &assets_dir, &mut built);
+    built.sort();
+    write_asset_hashes(&build_assets_dir, &built);
+    for path in &built {
+        loader::compress::precompress(&build_assets_dir.join(path.trim_start_matches('/')))
+            .unwrap_or_else(|err| panic!("failed to precompress {}: {}", path, err));
+    }
 }
@@ -0,0 +1,61 @@
+//! gzip and brotli encoders for text responses, run once ahead of time rather than per request.
+//!
+//! `build.rs` uses them for the stylesheet and scripts, the loader for SVG post images, and the
+//! server for the pages it renders at startup.
+
+use brotli::enc::BrotliEncoderParams;
+use flate2::{Compression, write::GzEncoder};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Extensions of the text formats served as files. Images such as PNG are already compressed.
+const COMPRESSIBLE: [&str; 3] = ["css", "js", "svg"];
+
+pub fn gzip(bytes: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    encoder
+        .write_all(bytes)
+        .and_then(|_| encoder.finish())
+        .expect("writing to a Vec cannot fail")
+}
+
+pub fn brotli(bytes: &[u8]) -> Vec<u8> {
+    let params = BrotliEncoderParams {
+        quality: 11,
+        ..Default::default()
+    };
+    let mut output = Vec::new();
+    brotli::BrotliCompress(&mut &bytes[..], &mut output, &params)
+        .expect("writing to a Vec cannot fail");
+    output
+}
+
+pub fn is_compressible(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| COMPRESSIBLE.contains(&ext))
+}
+
+/// Writes `{path}.gz` and `{path}.br` next to `path`, where `ServeDir` looks for precompressed
+/// variants. Files that aren't compressible are left alone.
+pub fn precompress(path: &Path) -> Result<(), String> {
+    if !is_compressible(path) {
+        return Ok(());
+    }
+    let bytes =
+        fs::read(path).map_err(|err| format!("failed to read {}: {}", path.display(), err))?;
+    for (suffix, encoded) in [("gz", gzip(&bytes)), ("br", brotli(&bytes))] {
+        let target = with_suffix(path, suffix);
+        fs::write(&target, encoded)
+            .map_err(|err| format!("failed to write {}: {}", target.display(), err))?;
+    }
+    Ok(())
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
@@ -0,0 +1,775 @@
+//! Post front matter: YAML between `---` lines or TOML between `+++` lines at the top of
+//! `index.md`.
+//!
+//! Both formats are parsed into the same list of top-level keys, each with the line it is on,
+//! then read into typed fields. Keys the blog doesn't know are ignored so other tools can keep
+//! their own metadata next to ours.
+
+use std::path::Path;
+use toml::de::{DeTable, DeValue};
+use yaml_rust2::{
+    Yaml, YamlLoader,
+    parser::{Event, MarkedEventReceiver, Parser},
+    scanner::Marker,
+};
+
+use crate::{PostDate, PostError, PostTime};
+
+pub(crate) struct FrontMatter {
+    pub title: String,
+    pub published: PostDate,
+    pub updated: Option<PostDate>,
+    pub draft: bool,
+    pub weight: i32,
+    pub tags: Vec<String>,
+    pub toc: bool,
+    pub description: Option<String>,
+    /// With the line of its key, for errors about the file it names.
+    pub image: Option<(String, usize)>,
+}
+
+#[derive(Clone, Copy)]
+enum Format {
+    Yaml,
+    Toml,
+}
+
+/// A front matter value, whichever format it was written in.
+enum Value {
+    Text(String),
+    Bool(bool),
+    Integer(i64),
+    List(Vec<Value>),
+    /// Fractional numbers, maps and nulls, which none of the keys the blog reads take. Holds
+    /// how to refer to the value in an error.
+    Other(&'static str),
+}
+
+struct Field {
+    key: String,
+    value: Value,
+    /// 1-based line of the post file the key is on.
+    line: usize,
+}
+
+/// Splits a post into its front matter, its markdown body and the line the body starts on.
+/// Every invalid key is reported, not just the first.
+pub(crate) fn parse(
+    content: &str,
+    path: &Path,
+) -> Result<(FrontMatter, String, usize), Vec<PostError>> {
+    let mut lines = content.lines();
+    let (format, delimiter) = match lines.next().map(str::trim) {
+        Some("---") => (Format::Yaml, "---"),
+        Some("+++") => (Format::Toml, "+++"),
+        _ => {
+            return Err(vec![PostError::at(
+                path,
+                1,
+                None,
+                "must start with front matter between --- (YAML) or +++ (TOML) lines",
+            )]);
+        }
+    };
+
+    let mut front = Vec::new();
+    let mut closed = false;
+    for line in lines.by_ref() {
+        if line.trim() == delimiter {
+            closed = true;
+            break;
+        }
+        front.push(line);
+    }
+    if !closed {
+        return Err(vec![PostError::at(
+            path,
+            1,
+            None,
+            format!("front matter is never closed with {}", delimiter),
+        )]);
+    }
+    let source = front.join("\n");
+    let body = lines.collect::<Vec<_>>().join("\n");
+
+    // The opening delimiter is line 1, so front matter line `n` is file line `n + 1`.
+    let fields = match format {
+        Format::Yaml => yaml_fields(&source, path, 1),
+        Format::Toml => toml_fields(&source, path, 1),
+    }
+    .map_err(|err| vec![err])?;
+    // Both delimiter lines come before the body.
+    let body_line = front.len() + 3;
+    Ok((FrontMatter::from_fields(fields, path)?, body, body_line))
+}
+
+impl FrontMatter {
+    fn from_fields(fields: Vec<Field>, path: &Path) -> Result<Self, Vec<PostError>> {
+        let mut errors = Vec::new();
+        let present = |key: &str| fields.iter().any(|field| field.key == key);
+        let missing = [
+            ("title", present("title")),
+            ("published", present("published")),
+            ("draft", present("draft")),
+        ];
+
+        let mut title = None;
+        let mut published = None;
+        let mut updated = None;
+        let mut draft = None;
+        let mut weight = 0;
+        let mut tags = Vec::new();
+        let mut toc = true;
+        let mut description = None;
+        let mut image = None;
+        for field in fields {
+            match field.key.as_str() {
+                "title" => title = keep(field.text(path), &mut errors),
+                "published" => published = keep(field.date(path), &mut errors),
+                "updated" => updated = keep(field.date(path), &mut errors),
+                "draft" => draft = keep(field.boolean(path), &mut errors),
+                "weight" => weight = keep(field.integer(path), &mut errors).unwrap_or(weight),
+                "toc" => toc = keep(field.boolean(path), &mut errors).unwrap_or(toc),
+                "tags" => tags = keep(field.tags(path), &mut errors).unwrap_or_default(),
+                "description" => description = keep(field.text(path), &mut errors),
+                "image" => {
+                    let line = field.line;
+                    image = keep(field.text(path), &mut errors).map(|image| (image, line));
+                }
+                _ => {}
+            }
+        }
+        for (key, present) in missing {
+            if !present {
+                errors.push(PostError::at(
+                    path,
+                    1,
+                    Some(key),
+                    "required front matter is missing",
+                ));
+            }
+        }
+
+        match (title, published, draft) {
+            (Some(title), Some(published), Some(draft)) if errors.is_empty() => {
+                tags.sort();
+                tags.dedup();
+                Ok(Self {
+                    title,
+                    published,
+                    updated,
+                    draft,
+                    weight,
+                    tags,
+                    toc,
+                    // Folded (`>`) and literal (`|`) scalars end with a newline.
+                    description: description
+                        .map(|description| description.trim().to_string())
+                        .filter(|description| !description.is_empty()),
+                    image: image.filter(|(image, _)| !image.trim().is_empty()),
+                })
+            }
+            _ => Err(errors),
+        }
+    }
+}
+
+/// The value of a well-formed field; a malformed one is recorded so the rest are still read.
+fn keep<T>(result: Result<T, PostError>, errors: &mut Vec<PostError>) -> Option<T> {
+    result.map_err(|err| errors.push(err)).ok()
+}
+
+impl Field {
+    fn error(&self, path: &Path, message: impl Into<String>) -> PostError {
+        PostError::at(path, self.line, Some(&self.key), message)
+    }
+
+    fn text(self, path: &Path) -> Result<String, PostError> {
+        match self.value {
+            Value::Text(text) => Ok(text),
+            ref other => Err(self.error(path, format!("expected text, found {}", other.kind()))),
+        }
+    }
+
+    fn boolean(self, path: &Path) -> Result<bool, PostError> {
+        match self.value {
+            Value::Bool(value) => Ok(value),
+            ref other => Err(self.error(
+                path,
+                format!("expected true or false, found {}", other.kind()),
+            )),
+        }
+    }
+
+    fn date(self, path: &Path) -> Result<PostDate, PostError> {
+        let Value::Text(text) = &self.value else {
+            let found = self.value.kind();
+            return Err(self.error(path, format!("expected {}, found {}", DATE_FORMATS, found)));
+        };
+        parse_date(text).map_err(|message| self.error(path, message))
+    }
+
+    fn integer(self, path: &Path) -> Result<i32, PostError> {
+        match self.value {
+            Value::Integer(value) => i32::try_from(value)
+                .map_err(|_| self.error(path, format!("{} is out of range", value))),
+            ref other => Err(self.error(
+                path,
+                format!("expected a whole number, found {}", other.kind()),
+            )),
+        }
+    }
+
+    /// A list of tags, or a single comma-separated string of them.
+    fn tags(self, path: &Path) -> Result<Vec<String>, PostError> {
+        let items = match &self.value {
+            Value::List(items) => items
+                .iter()
+                .map(|item| match item {
+                    Value::Text(tag) => Ok(tag.as_str()),
+                    other => Err(self.error(
+                        path,
+                        format!("expected a list of tags, found {} in it", other.kind()),
+                    )),
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            Value::Text(tags) => tags
+                .split(',')
+                .filter(|tag| !tag.trim().is_empty())
+                .collect(),
+            other => {
+                return Err(self.error(
+                    path,
+                    format!("expected a list of tags, found {}", other.kind()),
+                ));
+            }
+        };
+        items
+            .into_iter()
+            .map(|item| {
+                parse_tag(item).ok_or_else(|| {
+                    self.error(
+                        path,
+                        format!("invalid tag {:?} (expected letters, digits and -)", item),
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+impl Value {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Text(_) => "text",
+            Self::Bool(_) => "a boolean",
+            Self::Integer(_) => "a number (quote it to use it as text)",
+            Self::List(_) => "a list",
+            Self::Other(kind) => kind,
+        }
+    }
+
+    fn from_yaml(yaml: Yaml) -> Self {
+        match yaml {
+            Yaml::String(text) => Self::Text(text),
+            Yaml::Boolean(value) => Self::Bool(value),
+            Yaml::Array(items) => Self::List(items.into_iter().map(Self::from_yaml).collect()),
+            Yaml::Integer(value) => Self::Integer(value),
+            Yaml::Real(_) => Self::Other("a number (quote it to use it as text)"),
+            Yaml::Hash(_) => Self::Other("a map"),
+            Yaml::Null => Self::Other("nothing"),
+            Yaml::Alias(_) | Yaml::BadValue => Self::Other("an invalid value"),
+        }
+    }
+
+    fn from_toml(value: DeValue) -> Self {
+        match value {
+            DeValue::String(text) => Self::Text(text.into_owned()),
+            DeValue::Boolean(value) => Self::Bool(value),
+            // `published = 2026-04-12` is a TOML date or datetime; it is validated like a YAML
+            // one, so a local datetime without an offset is rejected there.
+            DeValue::Datetime(datetime) => Self::Text(datetime.to_string()),
+            DeValue::Array(items) => Self::List(
+                items
+                    .into_iter()
+                    .map(|item| Self::from_toml(item.into_inner()))
+                    .collect(),
+            ),
+            DeValue::Integer(value) => match i64::from_str_radix(value.as_str(), value.radix()) {
+                Ok(value) => Self::Integer(value),
+                Err(_) => Self::Other("a number too large to use"),
+            },
+            DeValue::Float(_) => Self::Other("a number (quote it to use it as text)"),
+            DeValue::Table(_) => Self::Other("a table"),
+        }
+    }
+}
+
+fn yaml_fields(source: &str, path: &Path, offset: usize) -> Result<Vec<Field>, PostError> {
+    // Keys are found first: `YamlLoader` rejects a repeated key too, but without saying which
+    // line it's on. Syntax errors are left for the loader to report.
+    let mut keys = KeyLines::default();
+    if Parser::new(source.chars()).load(&mut keys, false).is_ok() {
+        for (idx, (key, line)) in keys.keys.iter().enumerate() {
+            if keys.keys[..idx].iter().any(|(seen, _)| seen == key) {
+                return Err(PostError::at(
+                    path,
+                    line + offset,
+                    Some(key),
+                    "key appears twice",
+                ));
+            }
+        }
+    }
+
+    let documents = YamlLoader::load_from_str(source).map_err(|err| {
+        // `info` leaves out the position in the front matter, which is off by `offset` from
+        // the one in the file; the error carries the file line instead.
+        PostError::at(path, err.marker().line() + offset, None, err.info())
+    })?;
+    let hash = match documents.into_iter().next() {
+        None => return Ok(Vec::new()),
+        Some(Yaml::Hash(hash)) => hash,
+        Some(_) => {
+            return Err(PostError::at(
+                path,
+                1 + offset,
+                None,
+                "front matter must be a map of keys to values",
+            ));
+        }
+    };
+
+    let mut fields: Vec<Field> = Vec::new();
+    for (key, line) in keys.keys {
+        let line = line + offset;
+        let value = hash
+            .get(&Yaml::String(key.clone()))
+            .cloned()
+            .ok_or_else(|| PostError::at(path, line, None, "keys must be plain text"))?;
+        fields.push(Field {
+            key,
+            value: Value::from_yaml(value),
+            line,
+        });
+    }
+    Ok(fields)
+}
+
+/// Records each top-level key of a YAML map with the line it is on, in document order.
+/// `YamlLoader` drops positions, so the events are walked a second time to find them.
+#[derive(Default)]
+struct KeyLines {
+    depth: usize,
+    expect_key: bool,
+    keys: Vec<(String, usize)>,
+}
+
+impl MarkedEventReceiver for KeyLines {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        let top_level = self.depth == 1;
+        match event {
+            Event::MappingStart(..) | Event::SequenceStart(..) => {
+                if top_level {
+                    self.expect_key = !self.expect_key;
+                }
+                self.depth += 1;
+                if self.depth == 1 {
+                    self.expect_key = true;
+                }
+            }
+            Event::MappingEnd | Event::SequenceEnd => self.depth -= 1,
+            Event::Scalar(value, ..) if top_level => {
+                if self.expect_key {
+                    self.keys.push((value, mark.line()));
+                }
+                self.expect_key = !self.expect_key;
+            }
+            Event::Alias(_) if top_level => self.expect_key = !self.expect_key,
+            _ => {}
+        }
+    }
+}
+
+fn toml_fields(source: &str, path: &Path, offset: usize) -> Result<Vec<Field>, PostError> {
+    let line_of = |index: usize| source[..index.min(source.len())].matches('\n').count() + 1;
+    let table = DeTable::parse(source).map_err(|err| {
+        let line = err.span().map_or(1, |span| line_of(span.start));
+        PostError::at(path, line + offset, None, err.message().trim_end())
+    })?;
+    Ok(table
+        .into_inner()
+        .into_iter()
+        .map(|(key, value)| Field {
+            line: line_of(key.span().start) + offset,
+            key: key.into_inner().into_owned(),
+            value: Value::from_toml(value.into_inner()),
+        })
+        .collect())
+}
+
+const DATE_FORMATS: &str =
+    "a YYYY-MM-DD date or an RFC 3339 timestamp like 2026-04-12T09:30:00+02:00";
+
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// A `YYYY-MM-DD` date that exists in the Gregorian calendar, optionally followed by the rest
+/// of an RFC 3339 timestamp. Timestamps need a `Z` or numeric offset: without one, the moment
+/// a post goes live would depend on the time zone of the machine serving it.
+fn parse_date(value: &str) -> Result<PostDate, String> {
+    let invalid = || format!("expected {}, found {:?}", DATE_FORMATS, value);
+    let (date, time) = match (value.get(..10), value.get(10..)) {
+        (Some(date), Some("")) => (date, None),
+        (Some(date), Some(rest)) => match rest.strip_prefix(['T', 't', ' ']) {
+            Some(time) => (date, Some(time)),
+            None => return Err(invalid()),
+        },
+        _ => return Err(invalid()),
+    };
+    let field = |range: std::ops::Range<usize>| date.get(range).and_then(digits);
+    let (Some(year), Some(month), Some(day), Some("-"), Some("-")) = (
+        field(0..4),
+        field(5..7),
+        field(8..10),
+        date.get(4..5),
+        date.get(7..8),
+    ) else {
+        return Err(invalid());
+    };
+    if !(1..=12).contains(&month) {
+        return Err(format!(
+            "{} isn't a date: there is no month {}",
+            date, month
+        ));
+    }
+    let days = days_in_month(year, month);
+    if !(1..=days).contains(&day) {
+        return Err(format!(
+            "{} isn't a date: {} {} has {} days",
+            date,
+            MONTH_NAMES[usize::from(month) - 1],
+            year,
+            days
+        ));
+    }
+
+    let time = match time {
+        None => None,
+        Some(time) => match parse_time(time).ok_or_else(invalid)? {
+            (time, Some(offset)) => Some(PostTime { offset, ..time }),
+            (_, None) => {
+                return Err(format!(
+                    "{:?} needs a time zone, like {}Z or {}+02:00",
+                    value, value, value
+                ));
+            }
+        },
+    };
+    Ok(PostDate {
+        year,
+        month: month as u8,
+        day: day as u8,
+        time,
+    })
+}
+
+/// `HH:MM:SS`, with optional fractional seconds, then `Z` or `+HH:MM`/`-HH:MM`. The offset is
+/// `None` when the zone is left out; the fraction and leap seconds are dropped.
+fn parse_time(value: &str) -> Option<(PostTime, Option<i16>)> {
+    let (clock, zone) = value.split_at(value.find(['Z', 'z', '+', '-']).unwrap_or(value.len()));
+    let offset = match zone {
+        "" => None,
+        "Z" | "z" => Some(0),
+        _ => {
+            let (hours, minutes) = hours_minutes(&zone[1..])?;
+            let offset = (hours * 60 + minutes) as i16;
+            Some(if zone.starts_with('-') {
+                -offset
+            } else {
+                offset
+            })
+        }
+    };
+    let clock = match clock.split_once('.') {
+        Some((clock, fraction)) if all_digits(fraction) => clock,
+        Some(_) => return None,
+        None => clock,
+    };
+    let (hour, minute) = hours_minutes(clock.get(..5)?)?;
+    let second = digits(clock.get(5..)?.strip_prefix(':')?)?;
+    if clock.len() != 8 || second > 60 {
+        return None;
+    }
+    let time = PostTime {
+        hour: hour as u8,
+        minute: minute as u8,
+        second: second.min(59) as u8,
+        offset: 0,
+    };
+    Some((time, offset))
+}
+
+/// `HH:MM` on a 24-hour clock.
+fn hours_minutes(value: &str) -> Option<(u16, u16)> {
+    let (hours, minutes) = value.split_once(':')?;
+    if hours.len() != 2 || minutes.len() != 2 {
+        return None;
+    }
+    let (hours, minutes) = (digits(hours)?, digits(minutes)?);
+    (hours < 24 && minutes < 60).then_some((hours, minutes))
+}
+
+fn all_digits(value: &str) -> bool {
+    !value.is_empty() && value.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+fn digits(value: &str) -> Option<u16> {
+    all_digits(value).then(|| value.parse().ok()).flatten()
+}
+
+fn days_in_month(year: u16, month: u16) -> u16 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn parse_tag(value: &str) -> Option<String> {
+    let tag = value.trim().to_lowercase();
+    let valid = !tag.is_empty()
+        && tag
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-');
+    valid.then_some(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: u16, month: u8, day: u8) -> PostDate {
+        PostDate {
+            year,
+            month,
+            day,
+            time: None,
+        }
+    }
+
+    fn parse_str(content: &str) -> Result<(FrontMatter, String, usize), Vec<PostError>> {
+        parse(content, Path::new("content/post/bloom/index.md"))
+    }
+
+    fn error(content: &str) -> String {
+        match parse_str(content) {
+            Ok(_) => panic!("expected an error"),
+            Err(errors) => errors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+
+    #[test]
+    fn parses_yaml_front_matter() {
+        let (meta, body, body_line) = parse_str(
+            "---\n\
+             title: 'Broomfilter: making Rust as fast as C++'\n\
+             published: 2026-04-12\n\
+             draft: false\n\
+             tags:\n  - Rust\n  - performance\n\
+             description: >\n  Folded over\n  two lines.\n\
+             extra:\n  nested: map\n\
+             ---\n\n# Body",
+        )
+        .unwrap();
+
+        assert_eq!(meta.title, "Broomfilter: making Rust as fast as C++");
+        assert_eq!(meta.published, date(2026, 4, 12));
+        assert_eq!(meta.updated, None);
+        assert!(!meta.draft && meta.toc);
+        assert_eq!(meta.weight, 0);
+        assert_eq!(meta.tags, ["performance", "rust"]);
+        assert_eq!(meta.description.as_deref(), Some("Folded over two lines."));
+        assert_eq!(body, "\n# Body");
+        assert_eq!(body_line, 14);
+    }
+
+    #[test]
+    fn parses_toml_front_matter() {
+        let (meta, body, _) = parse_str(
+            "+++\n\
+             title = \"Bloom: filters\"\n\
+             published = 2025-11-10\n\
+             updated = 2025-12-01T18:45:00Z\n\
+             draft = true\n\
+             toc = false\n\
+             weight = 2\n\
+             tags = [\"hardware\", \"homelab\"]\n\
+             +++\nBody",
+        )
+        .unwrap();
+
+        assert_eq!(meta.title, "Bloom: filters");
+        assert_eq!(meta.published, date(2025, 11, 10));
+        assert_eq!(
+            meta.updated,
+            Some(PostDate {
+                time: Some(PostTime {
+                    hour: 18,
+                    minute: 45,
+                    second: 0,
+                    offset: 0,
+                }),
+                ..date(2025, 12, 1)
+            })
+        );
+        assert!(meta.draft && !meta.toc);
+        assert_eq!(meta.weight, 2);
+        assert_eq!(meta.tags, ["hardware", "homelab"]);
+        assert_eq!(body, "Body");
+    }
+
+    #[test]
+    fn reports_file_line_and_key() {
+        assert_eq!(
+            error("---\ntitle: Bloom\npublished: 2026-4-12\ndraft: false\n---\n"),
+            "content/post/bloom/index.md:3: `published`: expected a YYYY-MM-DD date or an RFC 3339 \
+             timestamp like 2026-04-12T09:30:00+02:00, found \"2026-4-12\""
+        );
+        assert_eq!(
+            error("---\ntitle: Bloom\npublished: 2026-04-12\ndraft: nope\n---\n"),
+            "content/post/bloom/index.md:4: `draft`: expected true or false, found text"
+        );
+        assert_eq!(
+            error(
+                "+++\ntitle = \"Bloom\"\npublished = 2026-04-12\ndraft = false\ntags = [\"a b\"]\n+++\n"
+            ),
+            "content/post/bloom/index.md:5: `tags`: invalid tag \"a b\" (expected letters, digits and -)"
+        );
+        assert_eq!(
+            error("---\ntitle: Bloom\npublished: 2026-04-12\ndraft: false\nweight: top\n---\n"),
+            "content/post/bloom/index.md:5: `weight`: expected a whole number, found text"
+        );
+        assert_eq!(
+            error("---\ntitle: Bloom\ndraft: false\n---\n"),
+            "content/post/bloom/index.md:1: `published`: required front matter is missing"
+        );
+        assert_eq!(
+            error("---\ntitle: Bloom\ntitle: Again\n---\n"),
+            "content/post/bloom/index.md:3: `title`: key appears twice"
+        );
+    }
+
+    #[test]
+    fn reports_every_invalid_key_at_once() {
+        assert_eq!(
+            error("---\ntitle: 1984\ndraft: maybe\n---\n"),
+            "content/post/bloom/index.md:2: `title`: expected text, found a number (quote it to use it as text)\n\
+             content/post/bloom/index.md:3: `draft`: expected true or false, found text\n\
+             content/post/bloom/index.md:1: `published`: required front matter is missing"
+        );
+    }
+
+    #[test]
+    fn reports_syntax_errors_at_their_line() {
+        assert!(
+            error("---\ntitle: Bloom\ntags: [rust\n---\n")
+                .starts_with("content/post/bloom/index.md:4: ")
+        );
+        assert!(
+            error("+++\ntitle = \"Bloom\"\ndraft = \n+++\n")
+                .starts_with("content/post/bloom/index.md:3: ")
+        );
+        assert!(error("title: Bloom\n").starts_with("content/post/bloom/index.md:1: must start"));
+        assert!(error("---\ntitle: Bloom\n").contains("never closed"));
+    }
+
+    #[test]
+    fn accepts_only_calendar_dates() {
+        assert_eq!(parse_date("2024-02-29"), Ok(date(2024, 2, 29)));
+        assert_eq!(parse_date("2000-02-29"), Ok(date(2000, 2, 29)));
+        assert_eq!(
+            parse_date("2026-02-29"),
+            Err("2026-02-29 isn't a date: February 2026 has 28 days".to_string())
+        );
+        assert_eq!(
+            parse_date("1900-02-29"),
+            Err("1900-02-29 isn't a date: February 1900 has 28 days".to_string())
+        );
+        assert_eq!(
+            parse_date("2026-04-31"),
+            Err("2026-04-31 isn't a date: April 2026 has 30 days".to_string())
+        );
+        assert_eq!(
+            parse_date("2026-13-01"),
+            Err("2026-13-01 isn't a date: there is no month 13".to_string())
+        );
+        assert!(parse_date("2026-00-10").is_err());
+        assert!(parse_date("2026-04-00").is_err());
+        assert!(parse_date("2026-04-1é").is_err());
+    }
+
+    #[test]
+    fn accepts_rfc_3339_timestamps_with_a_zone() {
+        let time = |hour, minute, second, offset| {
+            Some(PostTime {
+                hour,
+                minute,
+                second,
+                offset,
+            })
+        };
+        let at = |time| PostDate {
+            time,
+            ..date(2026, 4, 12)
+        };
+        assert_eq!(
+            parse_date("2026-04-12T09:30:00+02:00"),
+            Ok(at(time(9, 30, 0, 120)))
+        );
+        assert_eq!(
+            parse_date("2026-04-12t23:59:60.250z"),
+            Ok(at(time(23, 59, 59, 0)))
+        );
+        assert_eq!(
+            parse_date("2026-04-12 07:05:09-05:30"),
+            Ok(at(time(7, 5, 9, -330)))
+        );
+        assert_eq!(
+            parse_date("2026-04-12T09:30:00"),
+            Err(
+                "\"2026-04-12T09:30:00\" needs a time zone, like 2026-04-12T09:30:00Z or \
+                 2026-04-12T09:30:00+02:00"
+                    .to_string()
+            )
+        );
+        for invalid in [
+            "2026-04-12T24:00:00Z",
+            "2026-04-12T09:30Z",
+            "2026-04-12T09:30:00+0200",
+            "2026-04-12T09:30:00.Z",
+            "2026-04-12X09:30:00Z",
+        ] {
+            assert!(parse_date(invalid).is_err(), "{}", invalid);
+        }
+    }
+}
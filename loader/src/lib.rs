@@ -0,0 +1,364 @@
+//! Reads posts from `content/post`: front matter, markdown body and local images.
+//!
+//! Shared by the `Post` derive macro, which embeds the result at compile time, and by the
+//! server's `hot-reload` mode, which loads the same sources at runtime.
+
+pub mod compress;
+mod front_matter;
+
+use pulldown_cmark::{Event, Options, Parser, Tag};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub struct PostData {
+    pub id: String,
+    pub title: String,
+    pub published: PostDate,
+    /// When the post was last revised, from the `updated` key.
+    pub updated: Option<PostDate>,
+    pub draft: bool,
+    /// Listing position from the `weight` key: higher weights come first. 0 when unset.
+    pub weight: i32,
+    pub tags: Vec<String>,
+    pub toc: bool,
+    /// Summary for link previews, from the `description` key.
+    pub description: Option<String>,
+    /// Preview image URL from the `image` key. Local files are copied next to the post's other
+    /// images and point there.
+    pub image: Option<String>,
+    pub markdown: String,
+}
+
+/// A `published` or `updated` value: a calendar date, optionally with the time of day it was
+/// written with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PostDate {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub time: Option<PostTime>,
+}
+
+/// The time part of an RFC 3339 timestamp, in the time zone it was written in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PostTime {
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    /// Minutes east of UTC.
+    pub offset: i16,
+}
+
+/// Why a post couldn't be loaded, located as precisely as the loader knows: always the file,
+/// and the line and front matter key when the problem is in one.
+#[derive(Debug)]
+pub struct PostError {
+    /// Relative to the manifest directory.
+    pub path: PathBuf,
+    /// 1-based line of `path`.
+    pub line: Option<usize>,
+    pub key: Option<String>,
+    pub message: String,
+}
+
+impl PostError {
+    fn new(path: &Path, message: impl Into<String>) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            line: None,
+            key: None,
+            message: message.into(),
+        }
+    }
+
+    fn at(path: &Path, line: usize, key: Option<&str>, message: impl Into<String>) -> Self {
+        Self {
+            line: Some(line),
+            key: key.map(str::to_string),
+            ..Self::new(path, message)
+        }
+    }
+
+    fn relative_to(mut self, root: &Path) -> Self {
+        if let Ok(relative) = self.path.strip_prefix(root) {
+            self.path = relative.to_path_buf();
+        }
+        self
+    }
+}
+
+/// `content/post/bloom/index.md:3: `published`: expected ...`, the shape compilers and editors
+/// recognise as a location.
+impl fmt::Display for PostError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+        }
+        if let Some(key) = &self.key {
+            write!(f, ": `{}`", key)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl std::error::Error for PostError {}
+
+/// Parses every `content/post/*/index.md` under `manifest_dir`, sorted by directory name,
+/// and copies their local images to `build/img/{id}`, with gzip and brotli variants of SVGs.
+///
+/// A broken post doesn't stop the others from loading: every problem in every post is
+/// returned together, so one build reports them all.
+pub fn load_posts(manifest_dir: &Path) -> Result<Vec<PostData>, Vec<PostError>> {
+    let content_dir = manifest_dir.join("content").join("post");
+
+    let entries = fs::read_dir(&content_dir).map_err(|err| {
+        vec![
+            PostError::new(
+                &content_dir,
+                format!("failed to read post directory: {}", err),
+            )
+            .relative_to(manifest_dir),
+        ]
+    })?;
+
+    let mut post_dirs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_dir())
+        .collect();
+    post_dirs.sort();
+
+    let mut posts = Vec::new();
+    let mut errors = Vec::new();
+    for dir in post_dirs {
+        let id = dir
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let index_path = dir.join("index.md");
+        let post = if index_path.exists() {
+            parse_post(id, &index_path, manifest_dir)
+        } else {
+            Err(vec![PostError::new(
+                &index_path,
+                "is missing; every post directory needs one",
+            )])
+        };
+        match post {
+            Ok(post) => posts.push(post),
+            Err(post_errors) => errors.extend(
+                post_errors
+                    .into_iter()
+                    .map(|err| err.relative_to(manifest_dir)),
+            ),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(posts)
+    } else {
+        Err(errors)
+    }
+}
+
+fn parse_post(id: String, path: &Path, manifest_dir: &Path) -> Result<PostData, Vec<PostError>> {
+    let content = fs::read_to_string(path).map_err(|err| {
+        vec![PostError::new(
+            path,
+            format!("failed to read post file: {}", err),
+        )]
+    })?;
+    let (meta, markdown, body_line) = front_matter::parse(&content, path)?;
+    let post_dir = path.parent().unwrap_or(Path::new("."));
+
+    let mut errors = copy_post_images(&id, &markdown, post_dir, manifest_dir)
+        .into_iter()
+        .map(|(offset, message)| {
+            let line = body_line + markdown[..offset].matches('\n').count();
+            PostError::at(path, line, None, message)
+        })
+        .collect::<Vec<_>>();
+    let image = meta.image.and_then(|(image, line)| {
+        copy_cover_image(&id, &image, post_dir, manifest_dir)
+            .map_err(|message| errors.push(PostError::at(path, line, Some("image"), message)))
+            .ok()
+    });
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    Ok(PostData {
+        id,
+        title: meta.title,
+        published: meta.published,
+        updated: meta.updated,
+        draft: meta.draft,
+        weight: meta.weight,
+        tags: meta.tags,
+        toc: meta.toc,
+        description: meta.description,
+        image,
+        markdown,
+    })
+}
+
+/// Copies every local image the markdown references. Failures are returned with the byte
+/// offset of the image in `markdown`, and don't stop the remaining images from being copied.
+fn copy_post_images(
+    id: &str,
+    markdown: &str,
+    post_dir: &Path,
+    manifest_dir: &Path,
+) -> Vec<(usize, String)> {
+    let mut index = 0usize;
+    let mut errors = Vec::new();
+    // Same options the blog renders with, so images are numbered in the same order.
+    let options = Options::ENABLE_TABLES | Options::ENABLE_FOOTNOTES | Options::ENABLE_MATH;
+    for (event, range) in Parser::new_ext(markdown, options).into_offset_iter() {
+        let Event::Start(Tag::Image { dest_url, .. }) = event else {
+            continue;
+        };
+        let dest_url = dest_url.as_ref();
+        if !is_local_image(dest_url) {
+            continue;
+        }
+        index += 1;
+        if let Err(message) = copy_image(id, dest_url, &index.to_string(), post_dir, manifest_dir) {
+            errors.push((range.start, message));
+        }
+    }
+    errors
+}
+
+/// Copies the front matter `image` to `build/img/{id}/cover.{ext}` and returns its URL.
+/// Absolute and root-relative URLs are returned unchanged.
+fn copy_cover_image(
+    id: &str,
+    image: &str,
+    post_dir: &Path,
+    manifest_dir: &Path,
+) -> Result<String, String> {
+    if !is_local_image(image) {
+        return Ok(image.to_string());
+    }
+    copy_image(id, image, "cover", post_dir, manifest_dir)
+}
+
+/// Copies a local image referenced as `dest_url` to `build/img/{id}/{name}.{ext}`, looking
+/// next to the post first and then in its `img/` directory, and returns the URL it is served at.
+fn copy_image(
+    id: &str,
+    dest_url: &str,
+    name: &str,
+    post_dir: &Path,
+    manifest_dir: &Path,
+) -> Result<String, String> {
+    let extension = Path::new(dest_url)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .ok_or_else(|| format!("image {} has no file extension", dest_url))?;
+    let source = {
+        let direct = post_dir.join(dest_url);
+        if direct.exists() {
+            direct
+        } else {
+            post_dir.join("img").join(dest_url)
+        }
+    };
+    if !source.exists() {
+        return Err(format!(
+            "image {} is missing; looked for {}",
+            dest_url,
+            source
+                .strip_prefix(manifest_dir)
+                .unwrap_or(&source)
+                .display()
+        ));
+    }
+    let target_dir = manifest_dir.join("build").join("img").join(id);
+    fs::create_dir_all(&target_dir).map_err(|err| {
+        format!(
+            "failed to create image output directory {}: {}",
+            target_dir.display(),
+            err
+        )
+    })?;
+    let file_name = format!("{}.{}", name, extension);
+    let target = target_dir.join(&file_name);
+    fs::copy(&source, &target).map_err(|err| {
+        format!(
+            "failed to copy image from {} to {}: {}",
+            source.display(),
+            target.display(),
+            err
+        )
+    })?;
+    compress::precompress(&target)?;
+    Ok(format!("/img/{}/{}", id, file_name))
+}
+
+fn is_local_image(dest_url: &str) -> bool {
+    if dest_url.starts_with('/') {
+        return false;
+    }
+    if dest_url.starts_with("http://")
+        || dest_url.starts_with("https://")
+        || dest_url.starts_with("mailto:")
+        || dest_url.starts_with("data:")
+    {
+        return false;
+    }
+    !dest_url.contains("://")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn reports_every_broken_post_together() {
+        let root = std::env::temp_dir().join(format!("loader-errors-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let posts = root.join("content").join("post");
+        fs::create_dir_all(posts.join("empty")).unwrap();
+        write(
+            &posts.join("images").join("index.md"),
+            "---\ntitle: Images\npublished: 2026-04-12\ndraft: false\nimage: cover\n---\n\n\
+             Intro\n\n![chart](chart.png)\n\n![noext](chart)\n",
+        );
+        write(
+            &posts.join("dated").join("index.md"),
+            "---\ntitle: Dated\npublished: 12/04/2026\ndraft: false\n---\nBody\n",
+        );
+        write(
+            &posts.join("fine").join("index.md"),
+            "---\ntitle: Fine\npublished: 2026-04-12\ndraft: false\n---\nBody\n",
+        );
+
+        let errors = load_posts(&root)
+            .err()
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            [
+                "content/post/dated/index.md:3: `published`: expected a YYYY-MM-DD date or an \
+                 RFC 3339 timestamp like 2026-04-12T09:30:00+02:00, found \"12/04/2026\"",
+                "content/post/empty/index.md: is missing; every post directory needs one",
+                "content/post/images/index.md:10: image chart.png is missing; looked for content/post/images/img/chart.png",
+                "content/post/images/index.md:12: image chart has no file extension",
+                "content/post/images/index.md:5: `image`: image cover has no file extension",
+            ]
+        );
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -1,31 +1,100 @@
-use axum::{Router, routing::get};
+use axum::{Router, http::header::CACHE_CONTROL, middleware, routing::get};
+use clap::Parser;
 use std::sync::Arc;
-use tower_http::services::ServeDir;
+use tower_http::{services::ServeDir, set_header::SetResponseHeaderLayer};
+use tracing_subscriber::filter::LevelFilter;
 
+use blib::common::{REVALIDATE, cache_assets};
+use blib::config::{Command, Config};
 use blib::content;
 use blib::pages;
 use blib::state::AppState;
 
 #[tokio::main(flavor = "multi_thread")]
 async fn main() {
-    tracing_subscriber::fmt::init();
-    let state = Arc::new(AppState::new());
+    let config = Config::parse();
+    tracing_subscriber::fmt()
+        .with_max_level(LevelFilter::from(config.log_level))
+        .init();
+    if let Some(Command::Export { out_dir }) = &config.command {
+        let state = Arc::new(AppState::for_export(&config.asset_root));
+        match blib::export::export(state, &config.asset_root, out_dir).await {
+            Ok(summary) => println!(
+                "exported {} pages and {} assets to {}",
+                summary.pages,
+                summary.assets,
+                out_dir.display()
+            ),
+            Err(err) => {
+                eprintln!("export failed: {}", err);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
 
+    let state = Arc::new(
+        AppState::new(&config.asset_root).with_preview_secret(config.preview_secret.clone()),
+    );
+    if let Some(Command::PreviewLinks) = &config.command {
+        let Some(secret) = state.preview_secret() else {
+            eprintln!("preview links need --preview-secret or BLOG_PREVIEW_SECRET");
+            std::process::exit(1);
+        };
+        for post in state.post_state().unpublished() {
+            let path = format!(
+                "/preview/{}?token={}",
+                post.id,
+                content::preview_token(secret, post.id)
+            );
+            println!("{}\t{}", post.meta.title, state.site().url(&path));
+        }
+        return;
+    }
+
+    #[cfg(feature = "hot-reload")]
+    blib::state::watch_content(state.clone());
+
+    let serve_dir = |name| {
+        ServeDir::new(config.asset_dir(name))
+            .precompressed_br()
+            .precompressed_gzip()
+    };
+    let assets = Router::new()
+        .nest_service("/style", serve_dir("style"))
+        .nest_service("/img", serve_dir("img"))
+        .nest_service("/js", serve_dir("js"))
+        .layer(middleware::from_fn(cache_assets));
     let app = Router::new()
         .route("/", get(pages::index))
         .route("/about", get(pages::about))
         .route("/posts", get(pages::posts))
+        .route("/archive", get(pages::archive))
+        .route("/tags", get(pages::tags))
+        .route("/tags/{tag}", get(pages::tag))
+        .route("/search", get(pages::search))
         .route("/post/{id}", get(content::get_post))
-        .nest_service("/style", ServeDir::new("build/style"))
-        .nest_service("/img", ServeDir::new("build/img"))
-        .nest_service("/js", ServeDir::new("build/js"))
+        .route("/preview/{id}", get(content::get_preview))
+        .route("/og/{file}", get(content::get_card))
+        .route("/feed.xml", get(content::get_atom_feed))
+        .route("/rss.xml", get(content::get_rss_feed))
+        .route("/sitemap.xml", get(content::get_sitemap))
+        .route("/robots.txt", get(content::get_robots))
+        .merge(assets);
+    #[cfg(feature = "hot-reload")]
+    let app = app.route("/__livereload", get(blib::state::live_reload));
+    let app = app
         .fallback(pages::not_found)
+        .layer(SetResponseHeaderLayer::if_not_present(
+            CACHE_CONTROL,
+            REVALIDATE,
+        ))
         .with_state(state);
 
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000")
+    let listener = tokio::net::TcpListener::bind(config.socket_addr())
         .await
-        .expect("failed to bind");
+        .unwrap_or_else(|err| panic!("failed to bind {}: {}", config.socket_addr(), err));
     let local_addr = listener.local_addr().expect("failed to get local address");
-    println!("blog running at http://localhost:{}", local_addr.port());
+    println!("blog running at http://{}", local_addr);
     axum::serve(listener, app).await.expect("failed to serve");
 }
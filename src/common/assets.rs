@@ -0,0 +1,91 @@
+use axum::{
+    extract::{OriginalUri, Request},
+    http::{
+        HeaderValue,
+        header::{CACHE_CONTROL, VARY},
+    },
+    middleware::Next,
+    response::Response,
+};
+use loader::compress;
+use std::path::Path;
+
+/// `(url path, content hash)` for every asset `build.rs` wrote, e.g.
+/// `("/style/index.css", "3f2a9c0b1d4e5f60")`.
+const HASHES: &[(&str, &str)] = include!(concat!(env!("OUT_DIR"), "/asset_hashes.rs"));
+
+/// A year, the longest lifetime caches honour; the URL changes whenever the file does.
+const IMMUTABLE: HeaderValue = HeaderValue::from_static("public, max-age=31536000, immutable");
+
+/// `path` with its content hash appended as `?v=`, so a new build gets a new URL. Paths
+/// `build.rs` didn't produce, like post images, are returned unchanged.
+pub fn asset_url(path: &str) -> String {
+    match hash(path) {
+        Some(hash) => format!("{}?v={}", path, hash),
+        None => path.to_string(),
+    }
+}
+
+fn hash(path: &str) -> Option<&'static str> {
+    HASHES
+        .iter()
+        .find(|(asset, _)| *asset == path)
+        .map(|(_, hash)| *hash)
+}
+
+/// Whether the asset at `path` has `.br`/`.gz` variants: `build.rs` writes them for its
+/// stylesheet and scripts, and the loader for post SVGs, so that is every compressible file.
+fn is_precompressed(path: &str) -> bool {
+    compress::is_compressible(Path::new(path))
+}
+
+/// Middleware for the static asset services. A request carrying the current hash of the file
+/// can only ever get these bytes back, so it is cached as immutable; everything else is left to
+/// the router-wide `no-cache`, revalidated through `ServeDir`'s `Last-Modified`. `ServeDir`
+/// picks the `.br`/`.gz` variants by `Accept-Encoding` but doesn't say so, so shared caches are
+/// told here for the assets that have them.
+pub async fn cache_assets(request: Request, next: Next) -> Response {
+    // Nested services see the path with their prefix stripped.
+    let uri = match request.extensions().get::<OriginalUri>() {
+        Some(OriginalUri(uri)) => uri,
+        None => request.uri(),
+    };
+    let precompressed = is_precompressed(uri.path());
+    let versioned = uri
+        .query()
+        .and_then(|query| query.strip_prefix("v="))
+        .is_some_and(|version| hash(uri.path()) == Some(version));
+    let mut response = next.run(request).await;
+    if response.status().is_success() {
+        let headers = response.headers_mut();
+        if precompressed {
+            headers.insert(VARY, HeaderValue::from_static("accept-encoding"));
+        }
+        if versioned {
+            headers.insert(CACHE_CONTROL, IMMUTABLE);
+        }
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_built_assets_only() {
+        let url = asset_url("/style/index.css");
+
+        assert!(url.starts_with("/style/index.css?v="));
+        assert_eq!(url.len(), "/style/index.css?v=".len() + 16);
+        assert_eq!(asset_url("/img/bloom/1.png"), "/img/bloom/1.png");
+    }
+
+    #[test]
+    fn knows_which_assets_have_precompressed_variants() {
+        assert!(is_precompressed("/style/index.css"));
+        assert!(is_precompressed("/js/code-copy.js"));
+        assert!(!is_precompressed("/img/avatar.png"));
+        assert!(is_precompressed("/img/bloom/1.svg"));
+    }
+}
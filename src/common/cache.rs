@@ -0,0 +1,297 @@
+use axum::{
+    body::{Body, Bytes},
+    http::{
+        HeaderMap, HeaderValue, StatusCode,
+        header::{
+            ACCEPT_ENCODING, CACHE_CONTROL, CONTENT_ENCODING, CONTENT_TYPE, ETAG, IF_NONE_MATCH,
+            VARY,
+        },
+    },
+    response::{IntoResponse, Response},
+};
+use loader::compress;
+use sha2::{Digest, Sha256};
+
+/// Browsers may keep a copy but must revalidate it before every use. Pages and feeds change on
+/// deploy (or on every save with `hot-reload`), so they are never cached blindly; the ETag turns
+/// the revalidation into an empty 304.
+pub const REVALIDATE: HeaderValue = HeaderValue::from_static("no-cache");
+
+/// A response body rendered once at startup, compressed once with gzip and brotli, with a
+/// strong ETag per encoding derived from its bytes.
+pub struct Cached {
+    body: Bytes,
+    /// gzip and brotli variants of `body`, absent for formats that are compressed already.
+    compressed: Option<Compressed>,
+    /// First 128 bits of the SHA-256 of `body`, in hex.
+    hash: String,
+}
+
+struct Compressed {
+    gzip: Bytes,
+    brotli: Bytes,
+}
+
+/// Content codings a `Cached` body is stored in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Encoding {
+    Identity,
+    Gzip,
+    Brotli,
+}
+
+impl Cached {
+    /// A text body, served compressed to clients that accept it.
+    pub fn new(body: String) -> Self {
+        let compressed = Compressed {
+            gzip: compress::gzip(body.as_bytes()).into(),
+            brotli: compress::brotli(body.as_bytes()).into(),
+        };
+        Self::with_variants(body.into_bytes(), Some(compressed))
+    }
+
+    /// A body in a compressed format such as PNG, always served as is.
+    pub fn binary(body: Vec<u8>) -> Self {
+        Self::with_variants(body, None)
+    }
+
+    fn with_variants(body: Vec<u8>, compressed: Option<Compressed>) -> Self {
+        let hash = Sha256::digest(&body)
+            .iter()
+            .take(16)
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>();
+        Self {
+            body: body.into(),
+            compressed,
+            hash,
+        }
+    }
+
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// The body in the best encoding the request accepts, or `304 Not Modified` when its
+    /// `If-None-Match` already names that variant.
+    pub fn respond(&self, request: &HeaderMap, content_type: &'static str) -> Response {
+        let (encoding, body) = match &self.compressed {
+            None => (Encoding::Identity, &self.body),
+            Some(compressed) => match Encoding::negotiate(request) {
+                Encoding::Identity => (Encoding::Identity, &self.body),
+                Encoding::Gzip => (Encoding::Gzip, &compressed.gzip),
+                Encoding::Brotli => (Encoding::Brotli, &compressed.brotli),
+            },
+        };
+        let etag = self.etag(encoding);
+        let not_modified = matches_etag(request, &etag);
+        let mut response = if not_modified {
+            StatusCode::NOT_MODIFIED.into_response()
+        } else {
+            (
+                [(CONTENT_TYPE, HeaderValue::from_static(content_type))],
+                Body::from(body.clone()),
+            )
+                .into_response()
+        };
+
+        let headers = response.headers_mut();
+        headers.insert(ETAG, etag);
+        headers.insert(CACHE_CONTROL, REVALIDATE);
+        if self.compressed.is_some() {
+            headers.insert(VARY, HeaderValue::from_static("accept-encoding"));
+        }
+        if !not_modified && let Some(coding) = encoding.content_encoding() {
+            headers.insert(CONTENT_ENCODING, HeaderValue::from_static(coding));
+        }
+        response
+    }
+
+    /// Each encoding is a different representation, so it gets its own strong ETag.
+    fn etag(&self, encoding: Encoding) -> HeaderValue {
+        let suffix = match encoding.content_encoding() {
+            Some(coding) => format!("-{}", coding),
+            None => String::new(),
+        };
+        HeaderValue::from_str(&format!("\"{}{}\"", self.hash, suffix))
+            .expect("hex ETag is a valid header")
+    }
+}
+
+impl Encoding {
+    /// The accepted coding with the highest q-value, brotli first on ties since it compresses
+    /// better. Without an `Accept-Encoding` header the body is sent as is.
+    fn negotiate(request: &HeaderMap) -> Self {
+        let (mut brotli, mut gzip, mut any) = (None, None, None);
+        let codings = request
+            .get_all(ACCEPT_ENCODING)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','));
+        for coding in codings {
+            let mut params = coding.split(';').map(str::trim);
+            let name = params.next().unwrap_or_default().to_ascii_lowercase();
+            let quality = params
+                .find_map(|param| param.strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.parse::<f32>().ok())
+                .unwrap_or(0.0);
+            match name.as_str() {
+                "br" => brotli = Some(quality),
+                "gzip" | "x-gzip" => gzip = Some(quality),
+                "*" => any = Some(quality),
+                _ => {}
+            }
+        }
+
+        let brotli = brotli.or(any).unwrap_or(0.0);
+        let gzip = gzip.or(any).unwrap_or(0.0);
+        if brotli > 0.0 && brotli >= gzip {
+            Self::Brotli
+        } else if gzip > 0.0 {
+            Self::Gzip
+        } else {
+            Self::Identity
+        }
+    }
+
+    fn content_encoding(self) -> Option<&'static str> {
+        match self {
+            Self::Identity => None,
+            Self::Gzip => Some("gzip"),
+            Self::Brotli => Some("br"),
+        }
+    }
+}
+
+/// `If-None-Match` uses the weak comparison (RFC 9110 §13.1.2), so a `W/` prefix added by a
+/// proxy that recompressed the body still matches.
+fn matches_etag(request: &HeaderMap, etag: &HeaderValue) -> bool {
+    let etag = etag.as_bytes();
+    request
+        .get_all(IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/").as_bytes() == etag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(header: axum::http::HeaderName, value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    async fn body_bytes(response: Response) -> Bytes {
+        axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn derives_strong_etags_from_content() {
+        let page = Cached::new("<p>hello</p>".to_string());
+        let identity = page.etag(Encoding::Identity);
+        let tag = identity.to_str().unwrap();
+
+        assert_eq!(tag.len(), 34);
+        assert!(tag.starts_with('"') && tag.ends_with('"'));
+        assert_eq!(
+            identity,
+            Cached::new("<p>hello</p>".to_string()).etag(Encoding::Identity)
+        );
+        assert_ne!(
+            identity,
+            Cached::new("<p>hello!</p>".to_string()).etag(Encoding::Identity)
+        );
+        assert_eq!(
+            page.etag(Encoding::Brotli),
+            format!("{}-br\"", &tag[..33]).as_str()
+        );
+    }
+
+    #[test]
+    fn answers_matching_if_none_match_with_304() {
+        let page = Cached::new("<p>hello</p>".to_string());
+        let tag = page.etag(Encoding::Identity).to_str().unwrap().to_string();
+
+        let fresh = page.respond(&HeaderMap::new(), "text/html; charset=utf-8");
+        assert_eq!(fresh.status(), StatusCode::OK);
+        assert_eq!(fresh.headers()[ETAG], tag.as_str());
+        assert_eq!(fresh.headers()[CACHE_CONTROL], "no-cache");
+
+        for header in [
+            tag.clone(),
+            format!("\"other\", W/{}", tag),
+            "*".to_string(),
+        ] {
+            let cached = page.respond(&request(IF_NONE_MATCH, &header), "text/html; charset=utf-8");
+            assert_eq!(cached.status(), StatusCode::NOT_MODIFIED, "{}", header);
+            assert_eq!(cached.headers()[ETAG], tag.as_str());
+        }
+
+        let stale = page.respond(
+            &request(IF_NONE_MATCH, "\"other\""),
+            "text/html; charset=utf-8",
+        );
+        assert_eq!(stale.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn negotiates_content_encoding_by_quality() {
+        let cases = [
+            ("", Encoding::Identity),
+            ("gzip, deflate, br, zstd", Encoding::Brotli),
+            ("gzip", Encoding::Gzip),
+            ("br;q=0.5, gzip", Encoding::Gzip),
+            ("br;q=0, *", Encoding::Gzip),
+            ("*;q=0.1", Encoding::Brotli),
+            ("deflate, identity", Encoding::Identity),
+            ("gzip;q=0", Encoding::Identity),
+        ];
+        for (header, expected) in cases {
+            let encoding = Encoding::negotiate(&request(ACCEPT_ENCODING, header));
+            assert_eq!(encoding, expected, "{:?}", header);
+        }
+    }
+
+    #[tokio::test]
+    async fn serves_precompressed_variants() {
+        let body = "<p>hello</p>".repeat(100);
+        let page = Cached::new(body.clone());
+
+        let brotli = page.respond(
+            &request(ACCEPT_ENCODING, "gzip, br"),
+            "text/html; charset=utf-8",
+        );
+        assert_eq!(brotli.headers()[CONTENT_ENCODING], "br");
+        assert_eq!(brotli.headers()[VARY], "accept-encoding");
+        assert_eq!(body_bytes(brotli).await, compress::brotli(body.as_bytes()));
+
+        let gzip = page.respond(
+            &request(ACCEPT_ENCODING, "gzip"),
+            "text/html; charset=utf-8",
+        );
+        assert_eq!(gzip.headers()[CONTENT_ENCODING], "gzip");
+        let gzip = body_bytes(gzip).await;
+        assert!(gzip.starts_with(&[0x1f, 0x8b]) && gzip.len() < body.len());
+
+        let identity = page.respond(&HeaderMap::new(), "text/html; charset=utf-8");
+        assert!(!identity.headers().contains_key(CONTENT_ENCODING));
+        assert_eq!(body_bytes(identity).await, body.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn serves_binary_bodies_as_is() {
+        let png = Cached::binary(vec![0x89, b'P', b'N', b'G']);
+        let response = png.respond(&request(ACCEPT_ENCODING, "gzip, br"), "image/png");
+
+        assert!(!response.headers().contains_key(CONTENT_ENCODING));
+        assert!(!response.headers().contains_key(VARY));
+        assert_eq!(body_bytes(response).await, png.body());
+    }
+}
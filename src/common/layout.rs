@@ -1,10 +1,21 @@
 use maud::{DOCTYPE, Markup, html};
 
-pub fn layout(title: &str, content: Markup) -> Markup {
-    layout_with_head(title, content, None)
+use super::asset_url;
+use crate::state::Site;
+
+pub fn layout(site: &Site, title: &str, content: Markup) -> Markup {
+    layout_with_head(site, title, None, content, None)
 }
 
-pub fn layout_with_head(title: &str, content: Markup, head_extras: Option<Markup>) -> Markup {
+/// `description` replaces the site description in the `description` meta tag.
+pub fn layout_with_head(
+    site: &Site,
+    title: &str,
+    description: Option<&str>,
+    content: Markup,
+    head_extras: Option<Markup>,
+) -> Markup {
+    let description = description.unwrap_or(&site.description);
     html! {
         (DOCTYPE)
         html lang="en" {
@@ -12,22 +23,30 @@ pub fn layout_with_head(title: &str, content: Markup, head_extras: Option<Markup
                 meta charset="utf-8";
                 meta name="viewport" content="width=device-width, initial-scale=1";
                 title { (title) }
-                ;
-                link rel="stylesheet" href="/style/index.css";
-                link rel="icon" type="image/png" href="/img/avatar.png";
+                @if !description.is_empty() {
+                    meta name="description" content=(description);
+                }
+                meta name="author" content=(site.author);
+                link rel="stylesheet" href=(asset_url("/style/index.css"));
+                link rel="icon" type="image/png" href=(asset_url(&site.avatar));
+                link rel="alternate" type="application/atom+xml" title=(site.title) href="/feed.xml";
+                link rel="alternate" type="application/rss+xml" title=(site.title) href="/rss.xml";
+                @if cfg!(feature = "hot-reload") {
+                    script src=(asset_url("/js/live-reload.js")) defer {}
+                }
                 @if let Some(extras) = head_extras { (extras) }
             }
             body class="min-h-screen flex flex-col" {
                 main class="flex-1 w-full" {
                     div class="mb-6 flex w-full flex-wrap items-center gap-3 text-white" {
-                        h1 class="text-3xl font-semibold" { "Lucas' Hut" }
+                        h1 class="text-3xl font-semibold" { (site.title) }
                         span
                             class="text-white/70 font-light -translate-y-0.5 text-lg sm:text-base"
                         { "/" }
                         nav class="flex items-center gap-4 text-lg sm:text-base -translate-y-0.5" {
-                            a class="border-b-0 no-underline" href="/" { "Home" }
-                            a class="border-b-0 no-underline" href="/posts" { "Posts" }
-                            a class="border-b-0 no-underline" href="/about" { "About" }
+                            @for link in &site.nav {
+                                a class="border-b-0 no-underline" href=(link.href) { (link.label) }
+                            }
                         }
                     }
                     (content)
@@ -35,21 +54,16 @@ pub fn layout_with_head(title: &str, content: Markup, head_extras: Option<Markup
                 footer
                     class="site-footer mt-10 py-6 text-base sm:text-sm text-gray-400 flex flex-wrap justify-center text-center"
                 {
-                    a   href="https://www.linkedin.com/in/lucas-de-castro-swe/"
-                        target="_blank"
-                        rel="noopener noreferrer"
-                    {
-                        "linkedin"
-                    }
-                    span class="footer-separator" { "|" }
-                    a   href="https://github.com/ae2rs/"
-                        target="_blank"
-                        rel="noopener noreferrer"
-                    {
-                        "github"
+                    @for (idx, link) in site.social.iter().enumerate() {
+                        @if idx > 0 {
+                            span class="footer-separator" { "|" }
+                        }
+                        @if link.is_external() {
+                            a href=(link.href) target="_blank" rel="noopener noreferrer" { (link.label) }
+                        } @else {
+                            a href=(link.href) { (link.label) }
+                        }
                     }
-                    span class="footer-separator" { "|" }
-                    a href="mailto:lucas@decastro.one" { "lucas@decastro.one" }
                 }
             }
         }
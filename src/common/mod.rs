@@ -1,3 +1,7 @@
+mod assets;
+mod cache;
 mod layout;
 
+pub use assets::{asset_url, cache_assets};
+pub use cache::{Cached, REVALIDATE};
 pub use layout::{layout, layout_with_head};
@@ -0,0 +1,154 @@
+use clap::{Parser, Subcommand, ValueEnum};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::{Path, PathBuf},
+};
+use tracing_subscriber::filter::LevelFilter;
+
+/// Where `build.rs` writes styles, scripts and post images, relative to the working directory
+/// so the same binary works from the checkout and from the Docker image.
+const DEFAULT_ASSET_ROOT: &str = "build";
+
+const MIN_SECRET_LENGTH: usize = 16;
+
+/// Serves the blog.
+///
+/// Every flag can also be set through its environment variable; flags win when both are set.
+#[derive(Debug, Parser)]
+#[command(version)]
+pub struct Config {
+    /// Address to listen on.
+    #[arg(long, env = "BLOG_ADDRESS", default_value_t = IpAddr::V4(Ipv4Addr::UNSPECIFIED))]
+    pub address: IpAddr,
+
+    /// Port to listen on. 0 picks a free port.
+    #[arg(short, long, env = "BLOG_PORT", default_value_t = 3000)]
+    pub port: u16,
+
+    /// Directory holding the built `style/`, `img/` and `js/` assets.
+    #[arg(long, env = "BLOG_ASSET_ROOT", default_value = DEFAULT_ASSET_ROOT, value_parser = existing_dir)]
+    pub asset_root: PathBuf,
+
+    /// Most verbose log level to print.
+    #[arg(long, env = "BLOG_LOG_LEVEL", value_enum, default_value_t = LogLevel::Info)]
+    pub log_level: LogLevel,
+
+    /// Secret that `/preview/{id}` tokens are derived from. Draft previews are disabled without
+    /// one; changing it revokes every preview link handed out.
+    #[arg(long, env = "BLOG_PREVIEW_SECRET", hide_env_values = true, value_parser = preview_secret)]
+    pub preview_secret: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Write every page, feed and asset to a directory that any static file host can serve.
+    ///
+    /// Fails if a page links to a file or fragment the export doesn't contain.
+    Export {
+        /// Output directory; created if missing, existing files are overwritten.
+        out_dir: PathBuf,
+    },
+    /// Print the preview link of every draft and scheduled post. Needs `--preview-secret`.
+    PreviewLinks,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Config {
+    pub fn socket_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.address, self.port)
+    }
+
+    pub fn asset_dir(&self, name: &str) -> PathBuf {
+        self.asset_root.join(name)
+    }
+}
+
+impl From<LogLevel> for LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Off => LevelFilter::OFF,
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+/// Tokens are only as hard to guess as the secret behind them.
+fn preview_secret(value: &str) -> Result<String, String> {
+    if value.len() >= MIN_SECRET_LENGTH {
+        Ok(value.to_string())
+    } else {
+        Err(format!(
+            "must be at least {} characters long",
+            MIN_SECRET_LENGTH
+        ))
+    }
+}
+
+fn existing_dir(value: &str) -> Result<PathBuf, String> {
+    let path = Path::new(value);
+    if path.is_dir() {
+        Ok(path.to_path_buf())
+    } else {
+        Err(format!("{} is not a directory", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn validates_cli_definition() {
+        Config::command().debug_assert();
+    }
+
+    #[test]
+    fn parses_flags() {
+        let config = Config::try_parse_from([
+            "blog",
+            "--address",
+            "127.0.0.1",
+            "-p",
+            "8080",
+            "--asset-root",
+            env!("CARGO_MANIFEST_DIR"),
+            "--log-level",
+            "debug",
+        ])
+        .unwrap();
+
+        assert_eq!(config.socket_addr().to_string(), "127.0.0.1:8080");
+        assert_eq!(config.log_level, LogLevel::Debug);
+        assert_eq!(
+            config.asset_dir("img"),
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("img")
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        assert!(Config::try_parse_from(["blog", "--port", "70000"]).is_err());
+        assert!(Config::try_parse_from(["blog", "--address", "localhost:80"]).is_err());
+        assert!(Config::try_parse_from(["blog", "--asset-root", "/nonexistent/build"]).is_err());
+        assert!(Config::try_parse_from(["blog", "--log-level", "loud"]).is_err());
+        assert!(Config::try_parse_from(["blog", "export"]).is_err());
+        assert!(Config::try_parse_from(["blog", "--preview-secret", "hunter2"]).is_err());
+    }
+}
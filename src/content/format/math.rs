@@ -0,0 +1,791 @@
+//! TeX to MathML for the `$…$` and `$$…$$` formulas in posts, which browsers lay out natively
+//! without any script.
+//!
+//! Only the subset of TeX that short formulas in prose need is understood: letters, numbers and
+//! operators, `^`/`_` scripts and primes, `{}` groups, `\frac`, `\sqrt` (with an optional
+//! `[n]` index), `\left`/`\right` fences, Greek letters, common relations, arrows and binary
+//! operators, `\sum`/`\prod`/`\int`, upright function names such as `\log` and `\lim`, `\text`
+//! and the spacing commands. Matrices, environments, accents and font switches are left out on
+//! purpose: they are where a TeX implementation grows large, and the crates that cover them
+//! aren't worth a dependency for a few formulas. Anything outside the subset is reported as an
+//! unsupported command and the formula is shown as its source, so a post using it is noticed.
+
+use maud::{Markup, Render, html};
+
+const MATHML_NAMESPACE: &str = "http://www.w3.org/1998/Math/MathML";
+
+/// Typesets a TeX formula as MathML, or explains why it is outside the supported subset.
+pub fn render_math(tex: &str, display: bool) -> Result<Markup, String> {
+    let nodes = Parser::new(tex).parse()?;
+    Ok(html! {
+        math xmlns=(MATHML_NAMESPACE) display=[display.then_some("block")] {
+            semantics {
+                mrow {
+                    @for node in &nodes { (node) }
+                }
+                annotation encoding="application/x-tex" { (tex) }
+            }
+        }
+    })
+}
+
+/// Shown in place of a formula that failed to parse, keeping the source visible.
+pub fn render_math_error(tex: &str, display: bool, error: &str) -> Markup {
+    html! {
+        math xmlns=(MATHML_NAMESPACE) display=[display.then_some("block")] {
+            merror title=(error) {
+                mtext { (tex) }
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Node {
+    Ident {
+        text: String,
+        normal: bool,
+    },
+    Number(String),
+    Operator {
+        text: String,
+        kind: OperatorKind,
+    },
+    Text(String),
+    Space(&'static str),
+    Row(Vec<Node>),
+    Frac {
+        numerator: Box<Node>,
+        denominator: Box<Node>,
+    },
+    Sqrt(Box<Node>),
+    Root {
+        base: Box<Node>,
+        index: Box<Node>,
+    },
+    Scripts {
+        base: Box<Node>,
+        sub: Option<Box<Node>>,
+        sup: Option<Box<Node>>,
+        /// Places the scripts under and over the base, as for `\sum`.
+        limits: bool,
+    },
+    Fenced {
+        open: &'static str,
+        close: &'static str,
+        body: Vec<Node>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OperatorKind {
+    Normal,
+    /// Parentheses and bars outside `\left`/`\right` keep their natural size, as in TeX.
+    Fixed,
+    /// Big operators and functions like `\lim` that take their scripts as limits.
+    Limits,
+}
+
+impl Node {
+    fn ident(text: impl Into<String>) -> Self {
+        Self::Ident {
+            text: text.into(),
+            normal: false,
+        }
+    }
+
+    fn operator(text: impl Into<String>, kind: OperatorKind) -> Self {
+        Self::Operator {
+            text: text.into(),
+            kind,
+        }
+    }
+
+    fn group(mut nodes: Vec<Node>) -> Self {
+        if nodes.len() == 1 {
+            nodes.pop().expect("group has one node")
+        } else {
+            Self::Row(nodes)
+        }
+    }
+
+    fn takes_limits(&self) -> bool {
+        matches!(
+            self,
+            Self::Operator {
+                kind: OperatorKind::Limits,
+                ..
+            }
+        )
+    }
+}
+
+impl Render for Node {
+    fn render(&self) -> Markup {
+        match self {
+            Node::Ident { text, normal } => html! {
+                mi mathvariant=[(*normal && text.chars().count() == 1).then_some("normal")] { (text) }
+            },
+            Node::Number(text) => html! { mn { (text) } },
+            Node::Operator { text, kind } => match kind {
+                OperatorKind::Normal => html! { mo { (text) } },
+                OperatorKind::Fixed => html! { mo stretchy="false" { (text) } },
+                OperatorKind::Limits => html! { mo movablelimits="true" { (text) } },
+            },
+            Node::Text(text) => html! { mtext { (preserve_edge_spaces(text)) } },
+            Node::Space(width) => html! { mspace width=(width) {} },
+            Node::Row(nodes) => html! {
+                mrow { @for node in nodes { (node) } }
+            },
+            Node::Frac {
+                numerator,
+                denominator,
+            } => html! { mfrac { (numerator) (denominator) } },
+            Node::Sqrt(base) => html! { msqrt { (base) } },
+            Node::Root { base, index } => html! { mroot { (base) (index) } },
+            Node::Scripts {
+                base,
+                sub,
+                sup,
+                limits,
+            } => match (sub, sup, limits) {
+                (Some(sub), Some(sup), true) => html! { munderover { (base) (sub) (sup) } },
+                (Some(sub), None, true) => html! { munder { (base) (sub) } },
+                (None, Some(sup), true) => html! { mover { (base) (sup) } },
+                (Some(sub), Some(sup), false) => html! { msubsup { (base) (sub) (sup) } },
+                (Some(sub), None, false) => html! { msub { (base) (sub) } },
+                (None, Some(sup), false) => html! { msup { (base) (sup) } },
+                (None, None, _) => base.render(),
+            },
+            Node::Fenced { open, close, body } => html! {
+                mrow {
+                    @if !open.is_empty() { mo fence="true" stretchy="true" form="prefix" { (open) } }
+                    @for node in body { (node) }
+                    @if !close.is_empty() { mo fence="true" stretchy="true" form="postfix" { (close) } }
+                }
+            },
+        }
+    }
+}
+
+/// `mtext` collapses leading and trailing whitespace, which `\text{ if }` relies on.
+fn preserve_edge_spaces(text: &str) -> String {
+    let trimmed = text.trim_start();
+    let leading = text.len() - trimmed.len();
+    let trimmed = trimmed.trim_end();
+    let trailing = text.len() - leading - trimmed.len();
+    let mut preserved = "\u{a0}".repeat(leading.min(1));
+    preserved.push_str(trimmed);
+    preserved.push_str(&"\u{a0}".repeat(trailing.min(1)));
+    preserved
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(src: &'a str) -> Self {
+        Self { src, pos: 0 }
+    }
+
+    fn parse(mut self) -> Result<Vec<Node>, String> {
+        let nodes = self.parse_row()?;
+        match self.peek() {
+            None => Ok(nodes),
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.pos += ch.len_utf8();
+        Some(ch)
+    }
+
+    fn eat(&mut self, ch: char) -> bool {
+        if self.peek() == Some(ch) {
+            self.pos += ch.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    /// Name of the control sequence at the cursor: a run of letters, or a single symbol.
+    fn peek_command(&self) -> Option<&'a str> {
+        let rest = self.src[self.pos..].strip_prefix('\\')?;
+        let letters = rest
+            .find(|ch: char| !ch.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        if letters > 0 {
+            Some(&rest[..letters])
+        } else {
+            rest.chars().next().map(|ch| &rest[..ch.len_utf8()])
+        }
+    }
+
+    fn read_command(&mut self) -> Result<&'a str, String> {
+        let name = self
+            .peek_command()
+            .ok_or_else(|| "trailing `\\`".to_string())?;
+        self.pos += 1 + name.len();
+        Ok(name)
+    }
+
+    fn unexpected(&self) -> String {
+        match self.peek() {
+            Some('\\') => format!("unexpected `\\{}`", self.peek_command().unwrap_or_default()),
+            Some(ch) => format!("unexpected `{}`", ch),
+            None => "unexpected end of formula".to_string(),
+        }
+    }
+
+    /// Parses until a closing brace, `\right` or the end of input, leaving the terminator for
+    /// the caller.
+    fn parse_row(&mut self) -> Result<Vec<Node>, String> {
+        let mut nodes = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None | Some('}') => return Ok(nodes),
+                Some('\\') if self.peek_command() == Some("right") => return Ok(nodes),
+                _ => nodes.push(self.parse_scripted()?),
+            }
+        }
+    }
+
+    fn parse_group(&mut self) -> Result<Node, String> {
+        self.bump();
+        let nodes = self.parse_row()?;
+        if !self.eat('}') {
+            return Err(match self.peek() {
+                None => "missing `}`".to_string(),
+                _ => self.unexpected(),
+            });
+        }
+        Ok(Node::group(nodes))
+    }
+
+    fn parse_scripted(&mut self) -> Result<Node, String> {
+        let base = match self.peek() {
+            Some('^' | '_' | '\'') => Node::Row(Vec::new()),
+            _ => self.parse_atom()?,
+        };
+        let mut sub = None;
+        let mut sup = None;
+        let mut primes = String::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some('\'') => {
+                    self.bump();
+                    primes.push('′');
+                }
+                Some('^') => {
+                    self.bump();
+                    if sup.is_some() {
+                        return Err("double superscript".to_string());
+                    }
+                    sup = Some(self.parse_argument()?);
+                }
+                Some('_') => {
+                    self.bump();
+                    if sub.is_some() {
+                        return Err("double subscript".to_string());
+                    }
+                    sub = Some(self.parse_argument()?);
+                }
+                _ => break,
+            }
+        }
+
+        if !primes.is_empty() {
+            let prime = Node::operator(primes, OperatorKind::Normal);
+            sup = Some(match sup {
+                Some(sup) => Node::Row(vec![prime, sup]),
+                None => prime,
+            });
+        }
+        if sub.is_none() && sup.is_none() {
+            return Ok(base);
+        }
+        let limits = base.takes_limits();
+        Ok(Node::Scripts {
+            base: Box::new(base),
+            sub: sub.map(Box::new),
+            sup: sup.map(Box::new),
+            limits,
+        })
+    }
+
+    /// A single argument: a braced group, a command, or one character, so `x^23` only
+    /// raises the 2 like TeX does.
+    fn parse_argument(&mut self) -> Result<Node, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            None | Some('}' | '^' | '_') => Err("missing argument".to_string()),
+            Some(ch) if ch.is_ascii_digit() => {
+                self.bump();
+                Ok(Node::Number(ch.to_string()))
+            }
+            Some(_) => self.parse_atom(),
+        }
+    }
+
+    fn parse_atom(&mut self) -> Result<Node, String> {
+        let Some(ch) = self.peek() else {
+            return Err(self.unexpected());
+        };
+        match ch {
+            '{' => self.parse_group(),
+            '\\' => self.parse_command(),
+            '0'..='9' | '.' => Ok(self.parse_number()),
+            '~' => {
+                self.bump();
+                Ok(Node::Space("0.3333em"))
+            }
+            '}' | '^' | '_' => Err(self.unexpected()),
+            ch if ch.is_alphabetic() => {
+                self.bump();
+                Ok(Node::ident(ch))
+            }
+            ch => {
+                self.bump();
+                Ok(match ch {
+                    '-' => Node::operator("−", OperatorKind::Normal),
+                    '*' => Node::operator("∗", OperatorKind::Normal),
+                    '(' | ')' | '[' | ']' | '|' | '/' => Node::operator(ch, OperatorKind::Fixed),
+                    _ => Node::operator(ch, OperatorKind::Normal),
+                })
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> Node {
+        let start = self.pos;
+        let rest = &self.src[self.pos..];
+        let mut end = rest
+            .find(|ch: char| !ch.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let fraction = &rest[end..];
+        if let Some(digits) = fraction.strip_prefix('.')
+            && digits.starts_with(|ch: char| ch.is_ascii_digit())
+        {
+            end += 1 + digits
+                .find(|ch: char| !ch.is_ascii_digit())
+                .unwrap_or(digits.len());
+        }
+        if end == 0 {
+            // A lone `.` is punctuation, not a number.
+            self.bump();
+            return Node::operator(".", OperatorKind::Normal);
+        }
+        self.pos += end;
+        Node::Number(self.src[start..self.pos].to_string())
+    }
+
+    fn parse_command(&mut self) -> Result<Node, String> {
+        let name = self.read_command()?;
+        if let Some(letter) = greek_letter(name) {
+            return Ok(Node::Ident {
+                text: letter.to_string(),
+                normal: letter.is_uppercase(),
+            });
+        }
+        if let Some(symbol) = identifier_symbol(name) {
+            return Ok(Node::ident(symbol));
+        }
+        if let Some(symbol) = operator_symbol(name) {
+            return Ok(Node::operator(symbol, OperatorKind::Normal));
+        }
+        if let Some(symbol) = delimiter_symbol(name) {
+            return Ok(Node::operator(symbol, OperatorKind::Fixed));
+        }
+        if let Some(symbol) = large_operator(name) {
+            let kind = if symbol == "∫" {
+                OperatorKind::Normal
+            } else {
+                OperatorKind::Limits
+            };
+            return Ok(Node::operator(symbol, kind));
+        }
+        if let Some(width) = space_width(name) {
+            return Ok(Node::Space(width));
+        }
+        if let Some(limits) = function_name(name) {
+            return Ok(if limits {
+                Node::operator(name, OperatorKind::Limits)
+            } else {
+                Node::ident(name)
+            });
+        }
+
+        match name {
+            "frac" => {
+                let numerator = self.parse_argument()?;
+                let denominator = self.parse_argument()?;
+                Ok(Node::Frac {
+                    numerator: Box::new(numerator),
+                    denominator: Box::new(denominator),
+                })
+            }
+            "sqrt" => {
+                self.skip_whitespace();
+                let index = if self.eat('[') {
+                    let end = self.src[self.pos..]
+                        .find(']')
+                        .ok_or_else(|| "missing `]` after `\\sqrt[`".to_string())?;
+                    let index = &self.src[self.pos..self.pos + end];
+                    self.pos += end + 1;
+                    Some(Node::group(Parser::new(index).parse()?))
+                } else {
+                    None
+                };
+                let base = Box::new(self.parse_argument()?);
+                Ok(match index {
+                    Some(index) => Node::Root {
+                        base,
+                        index: Box::new(index),
+                    },
+                    None => Node::Sqrt(base),
+                })
+            }
+            "text" => Ok(Node::Text(unescape_text(self.read_braced_text()?))),
+            "left" => {
+                let open = self.parse_delimiter("left")?;
+                let body = self.parse_row()?;
+                if self.peek_command() != Some("right") {
+                    return Err("`\\left` without a matching `\\right`".to_string());
+                }
+                self.read_command()?;
+                let close = self.parse_delimiter("right")?;
+                Ok(Node::Fenced { open, close, body })
+            }
+            _ => Err(format!("unsupported command `\\{}`", name)),
+        }
+    }
+
+    fn parse_delimiter(&mut self, command: &str) -> Result<&'static str, String> {
+        self.skip_whitespace();
+        let delimiter = match self.peek() {
+            Some('\\') => {
+                let name = self.read_command()?;
+                delimiter_symbol(name)
+            }
+            Some(ch) => {
+                self.bump();
+                match ch {
+                    '(' => Some("("),
+                    ')' => Some(")"),
+                    '[' => Some("["),
+                    ']' => Some("]"),
+                    '|' => Some("|"),
+                    '.' => Some(""),
+                    _ => None,
+                }
+            }
+            None => None,
+        };
+        delimiter.ok_or_else(|| format!("missing delimiter after `\\{}`", command))
+    }
+
+    /// The raw contents of a braced argument, for `\text` which switches out of math mode.
+    fn read_braced_text(&mut self) -> Result<&'a str, String> {
+        self.skip_whitespace();
+        if !self.eat('{') {
+            return Err(format!("expected `{{` but found {}", self.unexpected()));
+        }
+        let start = self.pos;
+        let mut depth = 0usize;
+        while let Some(ch) = self.bump() {
+            match ch {
+                '\\' => {
+                    self.bump();
+                }
+                '{' => depth += 1,
+                '}' if depth == 0 => return Ok(&self.src[start..self.pos - 1]),
+                '}' => depth -= 1,
+                _ => {}
+            }
+        }
+        Err("missing `}`".to_string())
+    }
+}
+
+fn unescape_text(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => unescaped.extend(chars.next()),
+            '{' | '}' => {}
+            _ => unescaped.push(ch),
+        }
+    }
+    unescaped
+}
+
+fn greek_letter(name: &str) -> Option<char> {
+    Some(match name {
+        "alpha" => 'α',
+        "beta" => 'β',
+        "gamma" => 'γ',
+        "delta" => 'δ',
+        "epsilon" => 'ϵ',
+        "varepsilon" => 'ε',
+        "zeta" => 'ζ',
+        "eta" => 'η',
+        "theta" => 'θ',
+        "iota" => 'ι',
+        "kappa" => 'κ',
+        "lambda" => 'λ',
+        "mu" => 'μ',
+        "nu" => 'ν',
+        "xi" => 'ξ',
+        "pi" => 'π',
+        "rho" => 'ρ',
+        "sigma" => 'σ',
+        "tau" => 'τ',
+        "upsilon" => 'υ',
+        "phi" => 'ϕ',
+        "varphi" => 'φ',
+        "chi" => 'χ',
+        "psi" => 'ψ',
+        "omega" => 'ω',
+        "Gamma" => 'Γ',
+        "Delta" => 'Δ',
+        "Theta" => 'Θ',
+        "Lambda" => 'Λ',
+        "Xi" => 'Ξ',
+        "Pi" => 'Π',
+        "Sigma" => 'Σ',
+        "Phi" => 'Φ',
+        "Psi" => 'Ψ',
+        "Omega" => 'Ω',
+        _ => return None,
+    })
+}
+
+fn identifier_symbol(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "infty" => "∞",
+        "partial" => "∂",
+        "ell" => "ℓ",
+        "emptyset" => "∅",
+        "$" => "$",
+        "#" => "#",
+        _ => return None,
+    })
+}
+
+fn operator_symbol(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "cdot" => "⋅",
+        "times" => "×",
+        "div" => "÷",
+        "pm" => "±",
+        "circ" => "∘",
+        "oplus" => "⊕",
+        "le" | "leq" => "≤",
+        "ge" | "geq" => "≥",
+        "ne" | "neq" => "≠",
+        "approx" => "≈",
+        "equiv" => "≡",
+        "sim" => "∼",
+        "propto" => "∝",
+        "ll" => "≪",
+        "gg" => "≫",
+        "in" => "∈",
+        "notin" => "∉",
+        "subset" => "⊂",
+        "subseteq" => "⊆",
+        "cup" => "∪",
+        "cap" => "∩",
+        "setminus" => "∖",
+        "to" | "rightarrow" => "→",
+        "gets" | "leftarrow" => "←",
+        "Rightarrow" => "⇒",
+        "iff" => "⟺",
+        "mapsto" => "↦",
+        "land" | "wedge" => "∧",
+        "lor" | "vee" => "∨",
+        "neg" => "¬",
+        "forall" => "∀",
+        "exists" => "∃",
+        "ldots" | "dots" => "…",
+        "cdots" => "⋯",
+        "mid" => "∣",
+        "bmod" => "mod",
+        "%" => "%",
+        "&" => "&",
+        "_" => "_",
+        _ => return None,
+    })
+}
+
+fn delimiter_symbol(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "{" | "lbrace" => "{",
+        "}" | "rbrace" => "}",
+        "langle" => "⟨",
+        "rangle" => "⟩",
+        "lfloor" => "⌊",
+        "rfloor" => "⌋",
+        "lceil" => "⌈",
+        "rceil" => "⌉",
+        "|" => "‖",
+        _ => return None,
+    })
+}
+
+fn large_operator(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "sum" => "∑",
+        "prod" => "∏",
+        "int" => "∫",
+        _ => return None,
+    })
+}
+
+/// Upright function names, and whether they take their scripts as limits.
+fn function_name(name: &str) -> Option<bool> {
+    match name {
+        "lim" | "max" | "min" | "sup" | "inf" | "Pr" => Some(true),
+        "log" | "ln" | "exp" | "sin" | "cos" | "tan" => Some(false),
+        _ => None,
+    }
+}
+
+fn space_width(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "," => "0.1667em",
+        ":" => "0.2222em",
+        ";" => "0.2778em",
+        " " => "0.3333em",
+        "quad" => "1em",
+        "qquad" => "2em",
+        "!" => "-0.1667em",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mathml(tex: &str) -> String {
+        render_math(tex, false)
+            .unwrap_or_else(|err| panic!("{}: {}", tex, err))
+            .into_string()
+    }
+
+    fn body(tex: &str) -> String {
+        let html = mathml(tex);
+        let start = html.find("<mrow>").expect("formula row") + "<mrow>".len();
+        let end = html.rfind("</mrow><annotation").expect("annotation");
+        html[start..end].to_string()
+    }
+
+    #[test]
+    fn renders_fractions_and_scripts() {
+        assert_eq!(
+            body(r"\frac{a}{b^2}"),
+            "<mfrac><mi>a</mi><msup><mi>b</mi><mn>2</mn></msup></mfrac>"
+        );
+        assert_eq!(
+            body("x_i^{10}"),
+            "<msubsup><mi>x</mi><mi>i</mi><mn>10</mn></msubsup>"
+        );
+        assert_eq!(body("x^23"), "<msup><mi>x</mi><mn>2</mn></msup><mn>3</mn>");
+        assert_eq!(body("f'"), "<msup><mi>f</mi><mo>′</mo></msup>");
+    }
+
+    #[test]
+    fn renders_false_positive_rate() {
+        let html = body(r"\left(1 - e^{-kn/m}\right)^k");
+
+        assert!(html.starts_with(
+            "<msup><mrow><mo fence=\"true\" stretchy=\"true\" form=\"prefix\">(</mo>"
+        ));
+        assert!(html.contains("<mn>1</mn><mo>−</mo><msup><mi>e</mi><mrow><mo>−</mo><mi>k</mi><mi>n</mi><mo stretchy=\"false\">/</mo><mi>m</mi></mrow></msup>"));
+        assert!(html.ends_with("<mi>k</mi></msup>"));
+    }
+
+    #[test]
+    fn places_big_operator_scripts_as_limits() {
+        assert_eq!(
+            body(r"\sum_{i=1}^n i"),
+            "<munderover><mo movablelimits=\"true\">∑</mo><mrow><mi>i</mi><mo>=</mo><mn>1</mn></mrow><mi>n</mi></munderover><mi>i</mi>"
+        );
+        assert!(body(r"\int_0^1").starts_with("<msubsup><mo>∫</mo>"));
+        assert!(
+            body(r"\lim_{n \to \infty}").starts_with("<munder><mo movablelimits=\"true\">lim</mo>")
+        );
+    }
+
+    #[test]
+    fn maps_symbols_and_function_names() {
+        assert_eq!(
+            body(r"\alpha \le \Omega"),
+            "<mi>α</mi><mo>≤</mo><mi mathvariant=\"normal\">Ω</mi>"
+        );
+        assert_eq!(
+            body(r"\log_2 n"),
+            "<msub><mi>log</mi><mn>2</mn></msub><mi>n</mi>"
+        );
+        assert_eq!(
+            body(r"\text{ if } x"),
+            "<mtext>\u{a0}if\u{a0}</mtext><mi>x</mi>"
+        );
+    }
+
+    #[test]
+    fn escapes_markup_and_keeps_the_source_annotation() {
+        let html = render_math("a < b", true).unwrap().into_string();
+
+        assert!(
+            html.starts_with(
+                "<math xmlns=\"http://www.w3.org/1998/Math/MathML\" display=\"block\">"
+            )
+        );
+        assert!(html.contains("<mo>&lt;</mo>"));
+        assert!(html.contains("<annotation encoding=\"application/x-tex\">a &lt; b</annotation>"));
+    }
+
+    #[test]
+    fn reports_malformed_formulas() {
+        assert_eq!(
+            render_math(r"\frac{a}{b", false).unwrap_err(),
+            "missing `}`"
+        );
+        assert_eq!(
+            render_math(r"\foo", false).unwrap_err(),
+            "unsupported command `\\foo`"
+        );
+        assert_eq!(
+            render_math(r"\left( x", false).unwrap_err(),
+            "`\\left` without a matching `\\right`"
+        );
+        assert_eq!(
+            render_math("x^1^2", false).unwrap_err(),
+            "double superscript"
+        );
+        assert_eq!(render_math("a } b", false).unwrap_err(), "unexpected `}`");
+        assert_eq!(
+            render_math(r"\begin{pmatrix} a \end{pmatrix}", false).unwrap_err(),
+            "unsupported command `\\begin`"
+        );
+    }
+}
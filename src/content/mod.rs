@@ -1,4 +1,9 @@
 pub(crate) mod format;
 mod post;
 
-pub use post::{Post, PostState, get_post};
+#[cfg(feature = "hot-reload")]
+pub use post::load_posts;
+pub use post::{
+    Avatar, Date, Post, PostState, SearchHit, get_atom_feed, get_card, get_post, get_preview,
+    get_robots, get_rss_feed, get_sitemap, preview_token,
+};
@@ -0,0 +1,381 @@
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont, point};
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+use tiny_skia::{
+    Color, FillRule, FilterQuality, Mask, Paint, PathBuilder, Pattern, Pixmap, Rect, SpreadMode,
+    Transform,
+};
+
+use crate::{pages, state::AppState};
+
+/// The size OpenGraph and Twitter crop large previews to.
+pub const WIDTH: u32 = 1200;
+pub const HEIGHT: u32 = 630;
+
+const FONT: &[u8] = include_bytes!("../../../vendor/fonts/DejaVuSans-Bold.ttf");
+/// Drawn when the site's own avatar can't be loaded.
+const BUNDLED_AVATAR: &[u8] = include_bytes!("../../../assets/img/avatar.png");
+
+const MARGIN: f32 = 80.0;
+const AVATAR_SIZE: f32 = 96.0;
+/// Title sizes to try, largest first.
+const TITLE_SIZES: [f32; 3] = [72.0, 60.0, 50.0];
+const TITLE_LINES: usize = 3;
+/// Vertical centre of the title block, between the header and the footer.
+const TITLE_CENTER: f32 = 330.0;
+
+const BACKGROUND: [u8; 3] = [0x12, 0x12, 0x12];
+const TITLE: [u8; 3] = [0xf2, 0xf2, 0xf2];
+const MUTED: [u8; 3] = [0x9c, 0xa3, 0xaf];
+const ACCENT: [u8; 3] = [0x9b, 0xbc, 0xff];
+
+/// The round picture in a card's header.
+pub struct Avatar(Pixmap);
+
+impl Avatar {
+    /// The site's `avatar` as served from `asset_root`, e.g. `/img/avatar.png`. Falls back to
+    /// the bundled image, with a warning, when the file can't be read or isn't a PNG.
+    pub fn load(asset_root: &std::path::Path, url: &str) -> Self {
+        let path = asset_root.join(url.trim_start_matches('/'));
+        let avatar = std::fs::read(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|png| Pixmap::decode_png(&png).map_err(|err| err.to_string()));
+        match avatar {
+            Ok(avatar) => Self(avatar),
+            Err(err) => {
+                log::warn!(
+                    "card avatar {}: {}; using the bundled one",
+                    path.display(),
+                    err
+                );
+                Self::default()
+            }
+        }
+    }
+}
+
+impl Default for Avatar {
+    fn default() -> Self {
+        Self(Pixmap::decode_png(BUNDLED_AVATAR).expect("bundled avatar is a valid PNG"))
+    }
+}
+
+/// What a post's preview card shows.
+pub struct Card<'a> {
+    pub avatar: &'a Avatar,
+    pub title: &'a str,
+    pub date: &'a str,
+    pub site_title: &'a str,
+    /// Host the site is served from, e.g. `decastro.dev`.
+    pub domain: &'a str,
+}
+
+/// Draws a `WIDTH`×`HEIGHT` PNG in the site's colours: avatar and site title at the top, the
+/// post title wrapped over up to three lines, and the date and domain along the bottom.
+pub fn render_card(card: &Card) -> Result<Vec<u8>, String> {
+    let font = FontRef::try_from_slice(FONT).map_err(|err| format!("invalid font: {}", err))?;
+    let mut pixmap = Pixmap::new(WIDTH, HEIGHT).expect("card size is non-zero");
+    pixmap.fill(color(BACKGROUND));
+    fill_rect(
+        &mut pixmap,
+        0.0,
+        HEIGHT as f32 - 12.0,
+        WIDTH as f32,
+        12.0,
+        ACCENT,
+    );
+
+    draw_avatar(&mut pixmap, &card.avatar.0)?;
+    let mut text = Text::new(&font);
+    let header_baseline = MARGIN + AVATAR_SIZE / 2.0 + 12.0;
+    text.draw(
+        card.site_title,
+        MARGIN + AVATAR_SIZE + 28.0,
+        header_baseline,
+        34.0,
+        TITLE,
+    );
+
+    let max_width = WIDTH as f32 - 2.0 * MARGIN;
+    let (size, lines) = fit_title(&font, card.title, max_width);
+    let line_height = size * 1.2;
+    let block_top = TITLE_CENTER - lines.len() as f32 * line_height / 2.0;
+    for (idx, line) in lines.iter().enumerate() {
+        let baseline = block_top + idx as f32 * line_height + size * 0.95;
+        text.draw(line, MARGIN, baseline, size, TITLE);
+    }
+
+    let footer_baseline = HEIGHT as f32 - MARGIN;
+    text.draw(card.date, MARGIN, footer_baseline, 30.0, MUTED);
+    let domain_width = line_width(&font, card.domain, 30.0);
+    text.draw(
+        card.domain,
+        WIDTH as f32 - MARGIN - domain_width,
+        footer_baseline,
+        30.0,
+        ACCENT,
+    );
+    text.paint(&mut pixmap);
+
+    pixmap
+        .encode_png()
+        .map_err(|err| format!("failed to encode card: {}", err))
+}
+
+fn color([r, g, b]: [u8; 3]) -> Color {
+    Color::from_rgba8(r, g, b, 255)
+}
+
+fn paint(rgb: [u8; 3]) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color(color(rgb));
+    paint.anti_alias = true;
+    paint
+}
+
+fn fill_rect(pixmap: &mut Pixmap, x: f32, y: f32, width: f32, height: f32, rgb: [u8; 3]) {
+    if let Some(rect) = Rect::from_xywh(x, y, width, height) {
+        pixmap.fill_rect(rect, &paint(rgb), Transform::identity(), None);
+    }
+}
+
+/// The avatar scaled into a circle in the top-left corner.
+fn draw_avatar(pixmap: &mut Pixmap, avatar: &Pixmap) -> Result<(), String> {
+    let scale = AVATAR_SIZE / avatar.width().min(avatar.height()) as f32;
+    let circle = PathBuilder::from_circle(
+        MARGIN + AVATAR_SIZE / 2.0,
+        MARGIN + AVATAR_SIZE / 2.0,
+        AVATAR_SIZE / 2.0,
+    )
+    .ok_or("invalid avatar size")?;
+    let mut paint = paint(BACKGROUND);
+    paint.shader = Pattern::new(
+        avatar.as_ref(),
+        SpreadMode::Pad,
+        FilterQuality::Bicubic,
+        1.0,
+        Transform::from_scale(scale, scale).post_translate(MARGIN, MARGIN),
+    );
+    pixmap.fill_path(
+        &circle,
+        &paint,
+        FillRule::Winding,
+        Transform::identity(),
+        None,
+    );
+    Ok(())
+}
+
+/// The largest title size whose greedy word wrap fits in as few lines as possible, up to
+/// `TITLE_LINES`; two lines at a smaller size read better than three at a larger one. Titles
+/// too long even at the smallest size are cut with an ellipsis.
+fn fit_title(font: &FontRef, title: &str, max_width: f32) -> (f32, Vec<String>) {
+    for max_lines in 2..=TITLE_LINES {
+        for size in TITLE_SIZES {
+            let lines = wrap(font, title, size, max_width);
+            if lines.len() <= max_lines {
+                return (size, lines);
+            }
+        }
+    }
+
+    let size = TITLE_SIZES[TITLE_SIZES.len() - 1];
+    let mut lines = wrap(font, title, size, max_width);
+    lines.truncate(TITLE_LINES);
+    let last = lines.last_mut().expect("TITLE_LINES is non-zero");
+    while !last.is_empty() && line_width(font, &format!("{}…", last), size) > max_width {
+        last.truncate(last.rfind(' ').unwrap_or(0));
+    }
+    last.push('…');
+    (size, lines)
+}
+
+fn wrap(font: &FontRef, text: &str, size: f32, max_width: f32) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if line_width(font, &format!("{} {}", line, word), size) <= max_width => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_string()),
+        }
+    }
+    lines
+}
+
+fn line_width(font: &FontRef, text: &str, size: f32) -> f32 {
+    let font = font.as_scaled(PxScale::from(size));
+    let mut width = 0.0;
+    let mut previous = None;
+    for ch in text.chars() {
+        let id = font.glyph_id(ch);
+        if let Some(previous) = previous {
+            width += font.kern(previous, id);
+        }
+        width += font.h_advance(id);
+        previous = Some(id);
+    }
+    width
+}
+
+/// Glyph coverage collected into one mask per colour, then painted in a single fill each.
+struct Text<'a> {
+    font: &'a FontRef<'a>,
+    layers: Vec<([u8; 3], Mask)>,
+}
+
+impl<'a> Text<'a> {
+    fn new(font: &'a FontRef<'a>) -> Self {
+        Self {
+            font,
+            layers: Vec::new(),
+        }
+    }
+
+    fn draw(&mut self, text: &str, x: f32, baseline: f32, size: f32, rgb: [u8; 3]) {
+        let index = match self.layers.iter().position(|(layer, _)| *layer == rgb) {
+            Some(index) => index,
+            None => {
+                let mask = Mask::new(WIDTH, HEIGHT).expect("card size is non-zero");
+                self.layers.push((rgb, mask));
+                self.layers.len() - 1
+            }
+        };
+        let mask = self.layers[index].1.data_mut();
+
+        let scale = PxScale::from(size);
+        let scaled = self.font.as_scaled(scale);
+        let mut caret = x;
+        let mut previous = None;
+        for ch in text.chars() {
+            let id = scaled.glyph_id(ch);
+            if let Some(previous) = previous {
+                caret += scaled.kern(previous, id);
+            }
+            let glyph = id.with_scale_and_position(scale, point(caret, baseline));
+            caret += scaled.h_advance(id);
+            previous = Some(id);
+
+            let Some(outline) = self.font.outline_glyph(glyph) else {
+                continue;
+            };
+            let bounds = outline.px_bounds();
+            outline.draw(|gx, gy, coverage| {
+                let px = bounds.min.x as i32 + gx as i32;
+                let py = bounds.min.y as i32 + gy as i32;
+                if (0..WIDTH as i32).contains(&px) && (0..HEIGHT as i32).contains(&py) {
+                    let cell = &mut mask[py as usize * WIDTH as usize + px as usize];
+                    *cell = (*cell).max((coverage.clamp(0.0, 1.0) * 255.0) as u8);
+                }
+            });
+        }
+    }
+
+    fn paint(self, pixmap: &mut Pixmap) {
+        let area = Rect::from_xywh(0.0, 0.0, WIDTH as f32, HEIGHT as f32).expect("card size");
+        for (rgb, mask) in self.layers {
+            pixmap.fill_rect(area, &paint(rgb), Transform::identity(), Some(&mask));
+        }
+    }
+}
+
+pub async fn get_card(
+    State(state): State<Arc<AppState>>,
+    Path(file): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    let post_state = state.post_state();
+    let card = file
+        .strip_suffix(".png")
+        .and_then(|id| post_state.card(&id.to_lowercase()));
+    match card {
+        Some(card) => card.respond(&headers, "image/png"),
+        None => pages::not_found(State(state)).await.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn font() -> FontRef<'static> {
+        FontRef::try_from_slice(FONT).unwrap()
+    }
+
+    #[test]
+    fn renders_a_png_of_the_preview_size() {
+        let png = render_card(&Card {
+            avatar: &Avatar::default(),
+            title: "Broomfilter: making Rust as fast as C++",
+            date: "2026-04-12",
+            site_title: "Lucas' Hut",
+            domain: "decastro.dev",
+        })
+        .unwrap();
+        let pixmap = Pixmap::decode_png(&png).unwrap();
+
+        assert_eq!((pixmap.width(), pixmap.height()), (WIDTH, HEIGHT));
+        let corner = pixmap.pixel(WIDTH - 1, 0).unwrap();
+        assert_eq!([corner.red(), corner.green(), corner.blue()], BACKGROUND);
+    }
+
+    #[test]
+    fn draws_the_site_avatar_or_the_bundled_one() {
+        let root = std::env::temp_dir().join(format!("blog-card-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("img")).unwrap();
+        let mut red = Pixmap::new(8, 8).unwrap();
+        red.fill(color([0xff, 0x00, 0x00]));
+        red.save_png(root.join("img/me.png")).unwrap();
+
+        let centre = |avatar: &Avatar| {
+            let png = render_card(&Card {
+                avatar,
+                title: "Bloom filters",
+                date: "2026-04-12",
+                site_title: "Lucas' Hut",
+                domain: "decastro.dev",
+            })
+            .unwrap();
+            let middle = (MARGIN + AVATAR_SIZE / 2.0) as u32;
+            let pixel = Pixmap::decode_png(&png)
+                .unwrap()
+                .pixel(middle, middle)
+                .unwrap();
+            [pixel.red(), pixel.green(), pixel.blue()]
+        };
+        assert_eq!(
+            centre(&Avatar::load(&root, "/img/me.png")),
+            [0xff, 0x00, 0x00]
+        );
+        assert_eq!(
+            centre(&Avatar::load(&root, "/img/missing.png")),
+            centre(&Avatar::default())
+        );
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn shrinks_then_truncates_long_titles() {
+        let font = font();
+        let max_width = WIDTH as f32 - 2.0 * MARGIN;
+
+        let (size, lines) = fit_title(&font, "Short title", max_width);
+        assert_eq!((size, lines.len()), (TITLE_SIZES[0], 1));
+
+        let long = "a very long title that keeps going ".repeat(8);
+        let (size, lines) = fit_title(&font, &long, max_width);
+        assert_eq!(size, TITLE_SIZES[TITLE_SIZES.len() - 1]);
+        assert_eq!(lines.len(), TITLE_LINES);
+        assert!(lines[TITLE_LINES - 1].ends_with('…'));
+        assert!(
+            lines
+                .iter()
+                .all(|line| line_width(&font, line, size) <= max_width)
+        );
+    }
+}
@@ -0,0 +1,247 @@
+use axum::{extract::State, http::HeaderMap, response::Response};
+use maud::{PreEscaped, html};
+use std::sync::Arc;
+
+use super::types::Post;
+use crate::state::{AppState, Site};
+
+const XML_DECLARATION: &str = r#"<?xml version="1.0" encoding="utf-8"?>"#;
+
+/// A published post together with the HTML body `render_post` produced for it.
+pub struct FeedEntry<'a> {
+    pub post: &'a Post,
+    pub body: &'a str,
+}
+
+pub fn render_atom(site: &Site, entries: &[FeedEntry]) -> String {
+    let updated = entries
+        .iter()
+        .map(|entry| entry.post.meta.last_modified())
+        .max()
+        .map(|date| date.to_rfc3339())
+        .unwrap_or_else(|| "1970-01-01T00:00:00Z".to_string());
+    let feed = html! {
+        (PreEscaped(XML_DECLARATION))
+        feed xmlns="http://www.w3.org/2005/Atom" {
+            title { (site.title) }
+            @if !site.description.is_empty() {
+                subtitle { (site.description) }
+            }
+            id { (site.url("/")) }
+            link rel="alternate" type="text/html" href=(site.url("/")) {}
+            link rel="self" type="application/atom+xml" href=(site.url("/feed.xml")) {}
+            updated { (updated) }
+            author { name { (site.author) } }
+            @for entry in entries {
+                @let url = site.url(&post_path(entry.post));
+                entry {
+                    title { (entry.post.meta.title) }
+                    id { (url) }
+                    link rel="alternate" type="text/html" href=(url) {}
+                    published { (entry.post.meta.published.to_rfc3339()) }
+                    updated { (entry.post.meta.last_modified().to_rfc3339()) }
+                    @for tag in entry.post.meta.tags {
+                        category term=(tag) {}
+                    }
+                    content type="html" { (absolutize_links(site, entry.body)) }
+                }
+            }
+        }
+    };
+    feed.into_string()
+}
+
+pub fn render_rss(site: &Site, entries: &[FeedEntry]) -> String {
+    let feed = html! {
+        (PreEscaped(XML_DECLARATION))
+        rss version="2.0" {
+            channel {
+                title { (site.title) }
+                link { (site.url("/")) }
+                @if site.description.is_empty() {
+                    description { "Posts from " (site.title) }
+                } @else {
+                    description { (site.description) }
+                }
+                language { "en" }
+                @if let Some(latest) = entries.iter().map(|entry| entry.post.meta.last_modified()).max() {
+                    lastBuildDate { (latest.to_rfc2822()) }
+                }
+                @for entry in entries {
+                    @let url = site.url(&post_path(entry.post));
+                    item {
+                        title { (entry.post.meta.title) }
+                        link { (url) }
+                        guid isPermaLink="true" { (url) }
+                        pubDate { (entry.post.meta.published.to_rfc2822()) }
+                        @for tag in entry.post.meta.tags {
+                            category { (tag) }
+                        }
+                        description { (absolutize_links(site, entry.body)) }
+                    }
+                }
+            }
+        }
+    };
+    feed.into_string()
+}
+
+fn post_path(post: &Post) -> String {
+    format!("/post/{}", post.id)
+}
+
+/// Feed readers resolve links against the feed URL at best, so root-relative image and link
+/// targets in the rendered post are rewritten to absolute URLs. Protocol-relative `//host/…`
+/// targets already name their host and are left alone.
+fn absolutize_links(site: &Site, body: &str) -> String {
+    ["src=\"", "href=\""]
+        .into_iter()
+        .fold(body.to_string(), |body, attribute| {
+            let mut absolute = String::with_capacity(body.len());
+            let mut rest = body.as_str();
+            while let Some(idx) = rest.find(attribute) {
+                let (before, target) = rest.split_at(idx + attribute.len());
+                absolute.push_str(before);
+                if target.starts_with('/') && !target.starts_with("//") {
+                    absolute.push_str(&site.base_url);
+                }
+                rest = target;
+            }
+            absolute.push_str(rest);
+            absolute
+        })
+}
+
+pub async fn get_atom_feed(State(state): State<Arc<AppState>>, headers: HeaderMap) -> Response {
+    state
+        .post_state()
+        .atom_feed()
+        .respond(&headers, "application/atom+xml; charset=utf-8")
+}
+
+pub async fn get_rss_feed(State(state): State<Arc<AppState>>, headers: HeaderMap) -> Response {
+    state
+        .post_state()
+        .rss_feed()
+        .respond(&headers, "application/rss+xml; charset=utf-8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::post::types::{Date, PostMeta, Time};
+
+    const POST: Post = Post {
+        id: "bloom",
+        meta: PostMeta {
+            title: "Bloom & friends",
+            published: Date {
+                year: 2026,
+                month: 4,
+                day: 12,
+                time: None,
+            },
+            tags: &["rust"],
+            ..PostMeta::TEST
+        },
+        markdown: "",
+    };
+
+    fn site() -> Site {
+        Site::parse(
+            r#"
+            title = "Lucas' Hut"
+            author = "Lucas de Castro"
+            base_url = "https://decastro.dev"
+            "#,
+        )
+        .unwrap()
+    }
+
+    fn entries() -> Vec<FeedEntry<'static>> {
+        vec![FeedEntry {
+            post: &POST,
+            body: "<p>See <img src=\"/img/bloom/1.png\"></p>",
+        }]
+    }
+
+    #[test]
+    fn formats_feed_dates() {
+        let date = POST.meta.published;
+        assert_eq!(date.to_rfc3339(), "2026-04-12T00:00:00Z");
+        assert_eq!(date.to_rfc2822(), "Sun, 12 Apr 2026 00:00:00 +0000");
+
+        let date = Date {
+            time: Some(Time {
+                hour: 9,
+                minute: 30,
+                second: 5,
+                offset: -330,
+            }),
+            ..date
+        };
+        assert_eq!(date.to_rfc3339(), "2026-04-12T09:30:05-05:30");
+        assert_eq!(date.to_rfc2822(), "Sun, 12 Apr 2026 09:30:05 -0530");
+    }
+
+    #[test]
+    fn dates_atom_entries_by_their_last_update() {
+        let revised = Post {
+            meta: PostMeta {
+                updated: Some(Date {
+                    year: 2026,
+                    month: 5,
+                    day: 2,
+                    time: None,
+                }),
+                ..POST.meta
+            },
+            ..POST
+        };
+        let entries = [FeedEntry {
+            post: &revised,
+            body: "",
+        }];
+        let xml = render_atom(&site(), &entries);
+
+        assert!(xml.contains("<published>2026-04-12T00:00:00Z</published>"));
+        assert_eq!(
+            xml.matches("<updated>2026-05-02T00:00:00Z</updated>")
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn renders_atom_entries_with_escaped_content() {
+        let xml = render_atom(&site(), &entries());
+
+        assert!(xml.starts_with(XML_DECLARATION));
+        assert!(xml.contains("<title>Bloom &amp; friends</title>"));
+        assert!(xml.contains("<id>https://decastro.dev/post/bloom</id>"));
+        assert!(xml.contains("<published>2026-04-12T00:00:00Z</published>"));
+        assert!(xml.contains("<category term=\"rust\"></category>"));
+        assert!(xml.contains("&lt;img src=&quot;https://decastro.dev/img/bloom/1.png&quot;&gt;"));
+    }
+
+    #[test]
+    fn absolutizes_root_relative_links_only() {
+        let body = "<a href=\"/post/cuckoo\">x</a> <img src=\"//cdn.example.com/a.png\"> \
+                    <a href=\"https://example.com/\">y</a> <a href=\"#fn-1\">1</a>";
+        assert_eq!(
+            absolutize_links(&site(), body),
+            "<a href=\"https://decastro.dev/post/cuckoo\">x</a> \
+             <img src=\"//cdn.example.com/a.png\"> \
+             <a href=\"https://example.com/\">y</a> <a href=\"#fn-1\">1</a>"
+        );
+    }
+
+    #[test]
+    fn renders_rss_items() {
+        let xml = render_rss(&site(), &entries());
+
+        assert!(xml.contains("<rss version=\"2.0\">"));
+        assert!(xml.contains("<pubDate>Sun, 12 Apr 2026 00:00:00 +0000</pubDate>"));
+        assert!(xml.contains("<guid isPermaLink=\"true\">https://decastro.dev/post/bloom</guid>"));
+    }
+}
@@ -1,6 +1,21 @@
+mod card;
+mod feed;
 mod posts;
+mod preview;
+mod related;
 mod render;
+mod search;
+mod sitemap;
+#[cfg(feature = "hot-reload")]
+mod source;
 mod types;
 
+pub use card::{Avatar, get_card};
+pub use feed::{get_atom_feed, get_rss_feed};
 pub use posts::{PostState, get_post};
-pub use types::Post;
+pub use preview::{get_preview, preview_token};
+pub use search::SearchHit;
+pub use sitemap::{get_robots, get_sitemap};
+#[cfg(feature = "hot-reload")]
+pub use source::load_posts;
+pub use types::{Date, Post};
@@ -1,64 +1,654 @@
 use crate::{
-    common::layout_with_head, content::format::highlight::Highlighter, pages, state::AppState,
+    common::{Cached, asset_url, layout_with_head},
+    content::format::highlight::Highlighter,
+    pages,
+    state::{AppState, Site},
+};
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    response::{Html, IntoResponse, Response},
 };
-use axum::{extract::Path, extract::State, http::StatusCode, response::Html};
 use macros::Post;
-use maud::html;
-use std::{collections::HashMap, sync::Arc};
+use maud::{Markup, html};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
 
-use super::{render, types::Post};
+use super::{
+    card::{self, Avatar, Card},
+    feed::{self, FeedEntry},
+    related::RelatedPosts,
+    render::{self, Heading, RenderedPost},
+    search::{SearchHit, SearchIndex},
+    sitemap,
+    types::{Date, Post, ReadingTime},
+};
 
 #[derive(Post)]
 struct Posts;
 
 pub struct PostState {
+    /// Every post the state was built from, scheduled ones included, so it can be rebuilt once
+    /// they come due.
+    all_posts: Vec<&'static Post>,
+    /// The earliest `published` date after the moment the state was built for.
+    next_release: Option<Date>,
     posts: Vec<&'static Post>,
-    pages: HashMap<&'static str, String>,
+    pages: HashMap<&'static str, Cached>,
+    /// Drafts and scheduled posts, served at `/preview/{id}` to holders of a preview token.
+    unpublished: Vec<&'static Post>,
+    previews: HashMap<&'static str, Cached>,
+    /// Social preview PNGs, served at `/og/{id}.png`.
+    cards: HashMap<&'static str, Cached>,
+    /// Teasers shown under each post on the listings, for posts that have one.
+    excerpts: HashMap<&'static str, Markup>,
+    /// Shown next to each post on the listings.
+    reading_times: HashMap<&'static str, ReadingTime>,
+    tags: BTreeMap<&'static str, Vec<&'static Post>>,
+    search: SearchIndex,
+    atom_feed: Cached,
+    rss_feed: Cached,
+    sitemap: Cached,
+    robots: Cached,
 }
 
 impl PostState {
-    pub fn new(highlighter: &Highlighter) -> Self {
-        let posts = Posts::published_posts().clone();
+    /// Builds the state from the posts the `Post` derive embedded at compile time.
+    pub fn new(site: &Site, highlighter: &Highlighter, avatar: &Avatar) -> Self {
+        Self::from_posts(Posts::iter(), site, highlighter, avatar, Date::now())
+    }
+
+    /// Renders the posts published at or before `now`. Later ones stay out of every page,
+    /// feed and route until a state is built for their date.
+    pub fn from_posts(
+        posts: impl IntoIterator<Item = &'static Post>,
+        site: &Site,
+        highlighter: &Highlighter,
+        avatar: &Avatar,
+        now: Date,
+    ) -> Self {
+        let all_posts = posts.into_iter().collect::<Vec<_>>();
+        let next_release = all_posts
+            .iter()
+            .map(|post| post.meta.published)
+            .filter(|published| *published > now)
+            .min();
+        let posts = published_posts(all_posts.iter().copied(), now);
         let mut pages = HashMap::new();
-        for post in posts.iter().copied() {
-            pages.insert(post.id, render_post_page(post, highlighter));
+        let mut cards = HashMap::new();
+        let mut excerpts = HashMap::new();
+        let mut reading_times = HashMap::new();
+        let mut tags: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        let mut bodies = Vec::with_capacity(posts.len());
+        let related = RelatedPosts::new(&posts);
+        let mut by_date = posts.clone();
+        by_date.sort_by(|a, b| a.cmp_newest_first(b));
+        let neighbours = (0..by_date.len())
+            .map(|idx| (by_date[idx].id, Neighbours::at(&by_date, idx)))
+            .collect::<HashMap<_, _>>();
+        for (idx, post) in posts.iter().copied().enumerate() {
+            let rendered = render::render_post(post, highlighter);
+            let reading_time = post.reading_time();
+            reading_times.insert(post.id, reading_time);
+            match render_card(site, avatar, post) {
+                Ok(png) => {
+                    cards.insert(post.id, Cached::binary(png));
+                }
+                Err(err) => log::warn!("post {}: no preview card: {}", post.id, err),
+            }
+            let page = render_post_page(
+                site,
+                post,
+                &rendered,
+                reading_time,
+                neighbours[post.id],
+                related.of(idx),
+                PageKind::Published {
+                    has_card: cards.contains_key(post.id),
+                },
+            );
+            pages.insert(post.id, Cached::new(page));
+            if let Some(excerpt) = render::render_excerpt(post, highlighter) {
+                excerpts.insert(post.id, excerpt);
+            }
+            bodies.push(rendered.body.into_string());
+            for tag in post.meta.tags {
+                tags.entry(*tag).or_default().push(post);
+            }
+        }
+
+        let mut unpublished = all_posts
+            .iter()
+            .copied()
+            .filter(|post| post.meta.draft || post.meta.published > now)
+            .collect::<Vec<_>>();
+        unpublished.sort_by_key(|post| post.id);
+        let previews = unpublished
+            .iter()
+            .map(|post| {
+                let rendered = render::render_post(post, highlighter);
+                let page = render_post_page(
+                    site,
+                    post,
+                    &rendered,
+                    post.reading_time(),
+                    Neighbours::default(),
+                    &[],
+                    PageKind::Preview,
+                );
+                (post.id, Cached::new(page))
+            })
+            .collect();
+
+        // Feeds stay in date order; pinning is for the listings.
+        let mut entries = posts
+            .iter()
+            .zip(&bodies)
+            .map(|(post, body)| FeedEntry { post, body })
+            .collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.post.cmp_newest_first(b.post));
+        let atom_feed = Cached::new(feed::render_atom(site, &entries));
+        let rss_feed = Cached::new(feed::render_rss(site, &entries));
+        let search = SearchIndex::new(&posts);
+        let sitemap = Cached::new(sitemap::render_sitemap(site, &posts));
+        let robots = Cached::new(sitemap::render_robots(site));
+
+        Self {
+            all_posts,
+            next_release,
+            posts,
+            pages,
+            unpublished,
+            previews,
+            cards,
+            excerpts,
+            reading_times,
+            tags,
+            search,
+            atom_feed,
+            rss_feed,
+            sitemap,
+            robots,
         }
+    }
+
+    /// Whether a scheduled post has reached its publication date since this state was built.
+    pub fn is_due(&self, now: Date) -> bool {
+        self.next_release.is_some_and(|release| release <= now)
+    }
 
-        Self { posts, pages }
+    /// The same posts rendered again as of `now`.
+    pub fn rebuild(
+        &self,
+        site: &Site,
+        highlighter: &Highlighter,
+        avatar: &Avatar,
+        now: Date,
+    ) -> Self {
+        Self::from_posts(
+            self.all_posts.iter().copied(),
+            site,
+            highlighter,
+            avatar,
+            now,
+        )
     }
 
     pub fn posts(&self) -> &[&'static Post] {
         &self.posts
     }
 
-    pub fn page(&self, id: &str) -> Option<&String> {
+    pub fn page(&self, id: &str) -> Option<&Cached> {
         self.pages.get(id)
     }
+
+    /// Drafts and scheduled posts, by id.
+    pub fn unpublished(&self) -> &[&'static Post] {
+        &self.unpublished
+    }
+
+    pub fn preview(&self, id: &str) -> Option<&Cached> {
+        self.previews.get(id)
+    }
+
+    pub fn card(&self, id: &str) -> Option<&Cached> {
+        self.cards.get(id)
+    }
+
+    pub fn excerpt(&self, id: &str) -> Option<&Markup> {
+        self.excerpts.get(id)
+    }
+
+    pub fn reading_time(&self, id: &str) -> Option<ReadingTime> {
+        self.reading_times.get(id).copied()
+    }
+
+    pub fn tags(&self) -> &BTreeMap<&'static str, Vec<&'static Post>> {
+        &self.tags
+    }
+
+    pub fn tagged(&self, tag: &str) -> Option<&[&'static Post]> {
+        self.tags.get(tag).map(Vec::as_slice)
+    }
+
+    pub fn search(&self, query: &str) -> Vec<SearchHit> {
+        self.search.search(query)
+    }
+
+    pub fn atom_feed(&self) -> &Cached {
+        &self.atom_feed
+    }
+
+    pub fn rss_feed(&self) -> &Cached {
+        &self.rss_feed
+    }
+
+    pub fn sitemap(&self) -> &Cached {
+        &self.sitemap
+    }
+
+    pub fn robots(&self) -> &Cached {
+        &self.robots
+    }
 }
 
-fn render_post_page(post: &Post, highlighter: &Highlighter) -> String {
-    let published = post.meta.published.to_string();
+/// Posts published at or before `now`, in listing order. Drafts are only listed in debug builds;
+/// scheduled posts aren't listed in any build until their date.
+fn published_posts(
+    posts: impl IntoIterator<Item = &'static Post>,
+    now: Date,
+) -> Vec<&'static Post> {
+    let mut posts = posts
+        .into_iter()
+        .filter(|post| !post.meta.draft || cfg!(debug_assertions))
+        .filter(|post| post.meta.published <= now)
+        .collect::<Vec<_>>();
+    posts.sort_by(|a, b| a.cmp_listing(b));
+    posts
+}
+
+/// The posts published just before and after one, linked at the end of its page. Pinning
+/// only reorders the listings, so `weight` plays no part here.
+#[derive(Clone, Copy, Default)]
+struct Neighbours {
+    /// The older post.
+    previous: Option<&'static Post>,
+    /// The newer post.
+    next: Option<&'static Post>,
+}
+
+impl Neighbours {
+    /// `posts` is newest first, so the older post comes after `idx`.
+    fn at(posts: &[&'static Post], idx: usize) -> Self {
+        Self {
+            previous: posts.get(idx + 1).copied(),
+            next: idx.checked_sub(1).map(|idx| posts[idx]),
+        }
+    }
+}
+
+/// Where a post page is served from.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PageKind {
+    /// `/post/{id}`, along with `/og/{id}.png` when its card could be drawn.
+    Published { has_card: bool },
+    /// `/preview/{id}`, for a post that isn't published yet.
+    Preview,
+}
+
+/// A post's page. Previews of unpublished posts carry a banner saying so and `noindex`
+/// instead of the canonical and social tags, which would point at a `/post` URL that 404s.
+fn render_post_page(
+    site: &Site,
+    post: &Post,
+    rendered: &RenderedPost,
+    reading_time: ReadingTime,
+    neighbours: Neighbours,
+    related: &[&Post],
+    kind: PageKind,
+) -> String {
+    let preview = kind == PageKind::Preview;
+    let published = post.meta.published;
+    let show_toc = post.meta.toc && rendered.headings.len() > 1;
     let content = html! {
-        h1 class="text-5xl font-semibold tracking-tight text-white mt-10 mb-2" { (post.meta.title) }
-        time class="block mb-6 text-sm text-gray-500" datetime=(published) { (published) }
-        (render::render_post(post, highlighter))
+        div class="relative" {
+            @if preview {
+                p class="mt-8 rounded-xl border border-white/10 bg-white/5 px-4 py-3 text-sm text-white/80" {
+                    "Preview: this post isn't published yet. Please don't share this link."
+                }
+            }
+            h1 class="text-5xl font-semibold tracking-tight text-white mt-10 mb-2" { (post.meta.title) }
+            div class="mb-6 flex flex-wrap items-center gap-x-3 text-sm text-gray-500" {
+                time datetime=(published.to_w3c_datetime()) { (published) }
+                @if let Some(updated) = post.meta.revised() {
+                    span { "Updated " time datetime=(updated.to_w3c_datetime()) { (updated) } }
+                }
+                span { (reading_time.words_label()) " · " (reading_time.minutes_label()) }
+                @for tag in post.meta.tags {
+                    a class="border-b-0 text-gray-400 hover:text-white" href=(format!("/tags/{}", tag)) {
+                        "#" (tag)
+                    }
+                }
+            }
+            @if show_toc {
+                details class="xl:hidden mb-8 rounded-xl border border-white/10 bg-white/5 px-4 py-3" data-toc {
+                    summary class="cursor-pointer text-sm font-semibold text-white/80" { "Contents" }
+                    div class="mt-3" { (render_toc(&rendered.headings)) }
+                }
+            }
+            (rendered.body)
+            (render_related(related))
+            (render_neighbours(neighbours))
+            @if show_toc {
+                nav class="hidden xl:block absolute left-full top-0 ml-12 h-full w-56" aria-label="Table of contents" data-toc {
+                    div class="sticky top-8 max-h-[calc(100vh-4rem)] overflow-y-auto" {
+                        p class="mb-3 text-xs font-semibold uppercase tracking-[0.08em] text-white/50" { "Contents" }
+                        (render_toc(&rendered.headings))
+                    }
+                }
+            }
+        }
     };
+    let description = post.meta.description.or(rendered.summary.as_deref());
     let head_extras = html! {
-        script src="/js/code-copy.js" defer {}
-        script src="/js/scroll-spy.js" defer {}
+        @match kind {
+            PageKind::Published { has_card } => {
+                (render_social_meta(site, post, rendered, has_card))
+            }
+            PageKind::Preview => {
+                meta name="robots" content="noindex, nofollow";
+            }
+        }
+        script src=(asset_url("/js/code-copy.js")) defer {}
+        script src=(asset_url("/js/scroll-spy.js")) defer {}
     };
-    layout_with_head(post.meta.title, content, Some(head_extras)).into_string()
+    layout_with_head(
+        site,
+        post.meta.title,
+        description,
+        content,
+        Some(head_extras),
+    )
+    .into_string()
+}
+
+fn render_related(related: &[&Post]) -> Markup {
+    if related.is_empty() {
+        return html! {};
+    }
+    html! {
+        section class="mt-16" aria-label="Related posts" {
+            h2 class="text-xl font-semibold" { "Related posts" }
+            ul class="mt-4" {
+                @for post in related {
+                    li class="mb-2 list-none" {
+                        a class="text-white/80 hover:text-white" href=(format!("/post/{}", post.id)) {
+                            (post.meta.title)
+                        }
+                        span class="text-gray-500 whitespace-nowrap" { " · " (post.meta.published) }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn render_neighbours(neighbours: Neighbours) -> Markup {
+    if neighbours.previous.is_none() && neighbours.next.is_none() {
+        return html! {};
+    }
+    html! {
+        nav class="mt-16 flex gap-6 border-t border-white/10 pt-6 text-sm" aria-label="More posts" {
+            @if let Some(previous) = neighbours.previous {
+                a class="border-b-0 text-white/80 hover:text-white" href=(format!("/post/{}", previous.id)) rel="prev" {
+                    span class="block text-gray-500" { "‹ Previous" }
+                    (previous.meta.title)
+                }
+            }
+            @if let Some(next) = neighbours.next {
+                a class="ml-auto border-b-0 text-right text-white/80 hover:text-white" href=(format!("/post/{}", next.id)) rel="next" {
+                    span class="block text-gray-500" { "Next ›" }
+                    (next.meta.title)
+                }
+            }
+        }
+    }
+}
+
+/// Canonical URL plus the OpenGraph and Twitter card tags link previews are built from. The
+/// description and image come from front matter, falling back to the post's first paragraph
+/// and first image. Social networks don't display SVG, so posts without a raster image get
+/// their generated card, or the site's avatar if the card couldn't be drawn.
+fn render_social_meta(site: &Site, post: &Post, rendered: &RenderedPost, has_card: bool) -> Markup {
+    let url = site.url(&format!("/post/{}", post.id));
+    let description = post
+        .meta
+        .description
+        .or(rendered.summary.as_deref())
+        .unwrap_or(&site.description);
+    let image = post.meta.image.or(rendered
+        .first_image
+        .as_deref()
+        .filter(|image| !image.ends_with(".svg")));
+    let card_size = (image.is_none() && has_card).then_some((card::WIDTH, card::HEIGHT));
+    let image = match image {
+        Some(image) => absolute_url(site, image),
+        None if has_card => site.url(&format!("/og/{}.png", post.id)),
+        None => absolute_url(site, &site.avatar),
+    };
+    html! {
+        link rel="canonical" href=(url);
+        meta property="og:type" content="article";
+        meta property="og:site_name" content=(site.title);
+        meta property="og:title" content=(post.meta.title);
+        meta property="og:description" content=(description);
+        meta property="og:url" content=(url);
+        meta property="og:image" content=(image);
+        @if let Some((width, height)) = card_size {
+            meta property="og:image:width" content=(width);
+            meta property="og:image:height" content=(height);
+        }
+        meta property="article:published_time" content=(post.meta.published.to_rfc3339());
+        @if let Some(updated) = post.meta.updated {
+            meta property="article:modified_time" content=(updated.to_rfc3339());
+        }
+        meta property="article:author" content=(site.author);
+        @for tag in post.meta.tags {
+            meta property="article:tag" content=(tag);
+        }
+        meta name="twitter:card" content="summary_large_image";
+        meta name="twitter:title" content=(post.meta.title);
+        meta name="twitter:description" content=(description);
+        meta name="twitter:image" content=(image);
+    }
+}
+
+/// Crawlers fetch previews out of context, so root-relative URLs are made absolute.
+fn absolute_url(site: &Site, url: &str) -> String {
+    if url.starts_with('/') && !url.starts_with("//") {
+        site.url(url)
+    } else {
+        url.to_string()
+    }
+}
+
+fn render_card(site: &Site, avatar: &Avatar, post: &Post) -> Result<Vec<u8>, String> {
+    let date = post.meta.published.to_string();
+    let domain = site
+        .base_url
+        .split_once("://")
+        .map_or(site.base_url.as_str(), |(_, host)| host);
+    card::render_card(&Card {
+        avatar,
+        title: post.meta.title,
+        date: &date,
+        site_title: &site.title,
+        domain,
+    })
+}
+
+/// Headings as a flat list indented by depth below the post's top heading level.
+fn render_toc(headings: &[Heading]) -> Markup {
+    let top = headings
+        .iter()
+        .map(|heading| heading.level)
+        .min()
+        .unwrap_or(1);
+    html! {
+        ol class="space-y-1.5 text-sm leading-snug" {
+            @for heading in headings {
+                li class=(toc_indent(heading.level - top)) {
+                    a   class="border-b-0 text-gray-500 hover:text-white aria-[current=location]:text-white"
+                        href={ "#" (heading.slug) }
+                    {
+                        (heading.text)
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn toc_indent(depth: u8) -> &'static str {
+    match depth {
+        0 => "list-none",
+        1 => "list-none pl-3",
+        _ => "list-none pl-6",
+    }
 }
 
 pub async fn get_post(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
-) -> (StatusCode, Html<String>) {
+    headers: HeaderMap,
+) -> Response {
     let id = id.to_lowercase();
-    if let Some(page) = state.post_page(id.as_str()) {
-        return (StatusCode::OK, Html(page.clone()));
+    if let Some(page) = state.post_state().page(id.as_str()) {
+        return page.respond(&headers, "text/html; charset=utf-8");
+    }
+
+    let (status, page) = pages::not_found(State(state)).await;
+    (status, Html(page.into_string())).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::post::types::PostMeta;
+
+    const fn post(id: &'static str, day: u8) -> Post {
+        Post {
+            id,
+            meta: PostMeta {
+                title: "Bloom filters",
+                published: Date {
+                    year: 2025,
+                    month: 1,
+                    day,
+                    time: None,
+                },
+                ..PostMeta::TEST
+            },
+            markdown: "Bits and hashes.",
+        }
+    }
+
+    fn day(day: u8) -> Date {
+        Date {
+            year: 2025,
+            month: 1,
+            day,
+            time: None,
+        }
+    }
+
+    #[test]
+    fn holds_scheduled_posts_until_their_date() {
+        static POSTS: [Post; 2] = [post("live", 9), post("scheduled", 12)];
+        let site = Site::load();
+        let highlighter = Highlighter::default();
+        let ids = |state: &PostState| state.posts().iter().map(|post| post.id).collect::<Vec<_>>();
+
+        let state = PostState::from_posts(&POSTS, &site, &highlighter, &Avatar::default(), day(11));
+        assert_eq!(ids(&state), ["live"]);
+        assert!(state.page("scheduled").is_none());
+        assert!(!state.is_due(day(11)));
+        assert!(state.is_due(day(12)));
+
+        let state = state.rebuild(&site, &highlighter, &Avatar::default(), day(12));
+        assert_eq!(ids(&state), ["scheduled", "live"]);
+        assert!(state.page("scheduled").is_some());
+        assert!(!state.is_due(day(30)));
     }
 
-    let (status, page) = pages::not_found().await;
-    (status, Html(page.into_string()))
+    #[test]
+    fn lists_pinned_posts_first_and_breaks_date_ties_by_id() {
+        const PINNED: Post = post("pinned", 1);
+        static POSTS: [Post; 4] = [
+            post("b", 9),
+            post("old", 2),
+            Post {
+                meta: PostMeta {
+                    weight: 1,
+                    ..PINNED.meta
+                },
+                ..PINNED
+            },
+            post("a", 9),
+        ];
+        let site = Site::load();
+        let state = PostState::from_posts(
+            &POSTS,
+            &site,
+            &Highlighter::default(),
+            &Avatar::default(),
+            day(11),
+        );
+        let ids = state.posts().iter().map(|post| post.id).collect::<Vec<_>>();
+        assert_eq!(ids, ["pinned", "a", "b", "old"]);
+
+        let page = |id| String::from_utf8(state.page(id).unwrap().body().to_vec()).unwrap();
+        let links = |id| {
+            let page = page(id);
+            let link = |rel| {
+                ids.iter()
+                    .find(|id| page.contains(&format!("href=\"/post/{}\" rel=\"{}\"", id, rel)))
+                    .copied()
+            };
+            (link("prev"), link("next"))
+        };
+        // Previous is the older post and next the newer one, by date alone, so the pinned
+        // post sits at the old end rather than the top.
+        assert_eq!(links("pinned"), (None, Some("old")));
+        assert_eq!(links("old"), (Some("pinned"), Some("b")));
+        assert_eq!(links("b"), (Some("old"), Some("a")));
+        assert_eq!(links("a"), (Some("b"), None));
+
+        let feed = String::from_utf8(state.atom_feed().body().to_vec()).unwrap();
+        let entry = |id| feed.find(&format!("/post/{}<", id)).unwrap();
+        assert!(
+            entry("a") < entry("b") && entry("b") < entry("old") && entry("old") < entry("pinned")
+        );
+    }
+
+    #[test]
+    fn falls_back_to_the_avatar_without_a_card() {
+        static POST: Post = post("bloom", 9);
+        let site = Site::load();
+        let rendered = render::render_post(&POST, &Highlighter::default());
+        let image = |has_card| {
+            let html = render_social_meta(&site, &POST, &rendered, has_card).into_string();
+            let start = html.find("og:image\" content=\"").unwrap() + "og:image\" content=\"".len();
+            let end = start + html[start..].find('"').unwrap();
+            (
+                html[start..end].to_string(),
+                html.contains("og:image:width"),
+            )
+        };
+
+        assert_eq!(image(true), (site.url("/og/bloom.png"), true));
+        assert_eq!(image(false), (site.url(&site.avatar), false));
+    }
 }
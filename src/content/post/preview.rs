@@ -0,0 +1,130 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{
+        HeaderMap, HeaderValue,
+        header::{CACHE_CONTROL, REFERRER_POLICY},
+    },
+    response::{Html, IntoResponse, Response},
+};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+use crate::{pages, state::AppState};
+
+const BLOCK_SIZE: usize = 64;
+/// Hex characters kept from the HMAC; 128 bits is plenty against guessing over HTTP.
+const TOKEN_LENGTH: usize = 32;
+
+/// The token that unlocks `/preview/{id}`: an HMAC-SHA256 of the post id keyed with the server
+/// secret. Rotating the secret revokes every link handed out so far.
+pub fn preview_token(secret: &str, id: &str) -> String {
+    let mac = hmac_sha256(secret.as_bytes(), format!("preview:{}", id).as_bytes());
+    let mut token = mac
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+    token.truncate(TOKEN_LENGTH);
+    token
+}
+
+/// RFC 2104 HMAC over SHA-256.
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let pad = |byte: u8| block.map(|key| key ^ byte);
+
+    let inner = Sha256::new()
+        .chain_update(pad(0x36))
+        .chain_update(message)
+        .finalize();
+    Sha256::new()
+        .chain_update(pad(0x5c))
+        .chain_update(inner)
+        .finalize()
+        .into()
+}
+
+/// Compares every byte so the response time doesn't reveal how much of a guess was right.
+fn tokens_match(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+#[derive(Deserialize)]
+pub struct PreviewParams {
+    #[serde(default)]
+    token: String,
+}
+
+/// A draft or scheduled post for reviewers holding its link. Unknown posts, wrong tokens and
+/// servers without a preview secret all get the same 404, so the route doesn't reveal which
+/// drafts exist.
+pub async fn get_preview(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(params): Query<PreviewParams>,
+    headers: HeaderMap,
+) -> Response {
+    let id = id.to_lowercase();
+    let post_state = state.post_state();
+    let page = state
+        .preview_secret()
+        .filter(|secret| tokens_match(&preview_token(secret, &id), &params.token))
+        .and_then(|_| post_state.preview(&id));
+    let Some(page) = page else {
+        let (status, page) = pages::not_found(State(state)).await;
+        return (status, Html(page.into_string())).into_response();
+    };
+
+    let mut response = page.respond(&headers, "text/html; charset=utf-8");
+    let headers = response.headers_mut();
+    headers.insert(
+        "x-robots-tag",
+        HeaderValue::from_static("noindex, nofollow"),
+    );
+    // Shared caches would hand the draft to anyone; the token must not leak through links.
+    headers.insert(CACHE_CONTROL, HeaderValue::from_static("private, no-cache"));
+    headers.insert(REFERRER_POLICY, HeaderValue::from_static("no-referrer"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_rfc_4231_hmac() {
+        // Test case 2.
+        let mac = hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+        let hex = mac
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>();
+        assert_eq!(
+            hex,
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn derives_tokens_per_post_and_secret() {
+        let token = preview_token("correct horse battery", "bloom");
+
+        assert_eq!(token.len(), TOKEN_LENGTH);
+        assert_eq!(token, preview_token("correct horse battery", "bloom"));
+        assert_ne!(token, preview_token("correct horse battery", "bloom-2"));
+        assert_ne!(token, preview_token("another secret value", "bloom"));
+        assert!(tokens_match(&token, &token.clone()));
+        assert!(!tokens_match(&token, &token[1..]));
+        assert!(!tokens_match(&token, ""));
+    }
+}
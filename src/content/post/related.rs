@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use super::{search::term_weights, types::Post};
+
+const MAX_RELATED: usize = 3;
+/// Each shared tag counts as much as half an identical text.
+const SHARED_TAG_SCORE: f32 = 0.5;
+/// Below this, two posts only share incidental words.
+const MIN_SCORE: f32 = 0.1;
+
+/// The posts most like each published post, computed once at startup from shared tags and
+/// the cosine similarity of their TF-IDF term vectors. Terms are weighted the way search
+/// weights them, so titles and headings count for more than body text and code.
+pub struct RelatedPosts {
+    related: Vec<Vec<&'static Post>>,
+}
+
+impl RelatedPosts {
+    pub fn new(posts: &[&'static Post]) -> Self {
+        let documents = posts
+            .iter()
+            .map(|post| term_weights(post).0)
+            .collect::<Vec<_>>();
+        let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+        for terms in &documents {
+            for term in terms.keys() {
+                *document_frequency.entry(term).or_insert(0) += 1;
+            }
+        }
+
+        // Terms in every post say nothing about which ones are alike, and get an IDF of 0.
+        let vectors = documents
+            .iter()
+            .map(|terms| {
+                let mut vector = terms
+                    .iter()
+                    .map(|(term, weight)| {
+                        let idf =
+                            (posts.len() as f32 / document_frequency[term.as_str()] as f32).ln();
+                        (term.as_str(), (1.0 + weight).ln() * idf)
+                    })
+                    .filter(|(_, weight)| *weight > 0.0)
+                    .collect::<HashMap<_, _>>();
+                let norm = vector
+                    .values()
+                    .map(|weight| weight * weight)
+                    .sum::<f32>()
+                    .sqrt();
+                if norm > 0.0 {
+                    for weight in vector.values_mut() {
+                        *weight /= norm;
+                    }
+                }
+                vector
+            })
+            .collect::<Vec<_>>();
+
+        let related = posts
+            .iter()
+            .enumerate()
+            .map(|(idx, post)| {
+                let mut scored = posts
+                    .iter()
+                    .enumerate()
+                    .filter(|(other, _)| *other != idx)
+                    .map(|(other, other_post)| {
+                        let shared_tags = post
+                            .meta
+                            .tags
+                            .iter()
+                            .filter(|tag| other_post.meta.tags.contains(tag))
+                            .count();
+                        let score = shared_tags as f32 * SHARED_TAG_SCORE
+                            + similarity(&vectors[idx], &vectors[other]);
+                        (other, score)
+                    })
+                    .filter(|(_, score)| *score >= MIN_SCORE)
+                    .collect::<Vec<_>>();
+                // Equal scores keep listing order, so the pick is the same on every build.
+                scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+                scored
+                    .into_iter()
+                    .take(MAX_RELATED)
+                    .map(|(other, _)| posts[other])
+                    .collect()
+            })
+            .collect();
+
+        Self { related }
+    }
+
+    /// Most related first, for the post at `idx` in the slice the index was built from.
+    pub fn of(&self, idx: usize) -> &[&'static Post] {
+        self.related.get(idx).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Cosine similarity of two unit vectors.
+fn similarity(a: &HashMap<&str, f32>, b: &HashMap<&str, f32>) -> f32 {
+    let (small, large) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    small
+        .iter()
+        .filter_map(|(term, weight)| large.get(term).map(|other| weight * other))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::post::types::{Date, PostMeta};
+
+    const fn post(id: &'static str, tags: &'static [&'static str], markdown: &'static str) -> Post {
+        Post {
+            id,
+            meta: PostMeta {
+                title: "Notes",
+                published: Date {
+                    year: 2026,
+                    month: 4,
+                    day: 12,
+                    time: None,
+                },
+                tags,
+                ..PostMeta::TEST
+            },
+            markdown,
+        }
+    }
+
+    static POSTS: [Post; 4] = [
+        post(
+            "bloom",
+            &["rust"],
+            "Bloom filters hash every key into a bit array.",
+        ),
+        post(
+            "cuckoo",
+            &[],
+            "Cuckoo filters beat bloom filters when every key can be deleted.",
+        ),
+        post("pin", &["rust", "async"], "Pinning futures in async code."),
+        post("sata", &["hardware"], "Cheap SATA controllers drop disks."),
+    ];
+
+    fn related(idx: usize) -> Vec<&'static str> {
+        let posts = POSTS.iter().collect::<Vec<_>>();
+        RelatedPosts::new(&posts)
+            .of(idx)
+            .iter()
+            .map(|post| post.id)
+            .collect()
+    }
+
+    #[test]
+    fn ranks_by_shared_tags_and_terms() {
+        assert_eq!(related(0), ["pin", "cuckoo"]);
+        assert_eq!(related(1), ["bloom"]);
+        assert_eq!(related(2), ["bloom"]);
+        assert!(related(3).is_empty());
+        assert!(RelatedPosts::new(&[]).of(0).is_empty());
+    }
+}
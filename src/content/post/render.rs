@@ -1,11 +1,14 @@
 use maud::{Markup, PreEscaped, html};
 use pulldown_cmark::{
-    CodeBlockKind, CowStr, Event, HeadingLevel, Options, Parser, Tag, TextMergeStream,
+    CodeBlockKind, CowStr, Event, HeadingLevel, Parser, Tag, TagEnd, TextMergeStream,
 };
 use std::{collections::HashMap, path::Path};
 
-use super::types::{Frame, FrameKind, Post, RenderNode};
-use crate::{component::icons, content::format::highlight::Highlighter};
+use super::types::{Frame, FrameKind, MARKDOWN_OPTIONS, Post, RenderNode};
+use crate::{
+    component::icons,
+    content::format::{highlight::Highlighter, math},
+};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum CalloutKind {
@@ -88,26 +91,162 @@ impl CalloutKind {
     }
 }
 
-pub fn render_post(post: &Post, highlighter: &Highlighter) -> Markup {
-    let mut slug_counts = HashMap::new();
-    let mut image_index = 0usize;
+/// Longest summary taken from the first paragraph, in characters; about what link previews
+/// show before cutting off.
+const SUMMARY_LENGTH: usize = 200;
+
+/// Marks the end of a post's excerpt. Everything before it is shown on the listings when the
+/// post has no `description`.
+const MORE_MARKER: &str = "<!-- more -->";
+
+/// A rendered post body along with the headings it contains, in document order.
+pub struct RenderedPost {
+    pub body: Markup,
+    pub headings: Vec<Heading>,
+    /// Plain text of the first paragraph, shortened to `SUMMARY_LENGTH`.
+    pub summary: Option<String>,
+    /// URL of the first image, as it appears in `body`.
+    pub first_image: Option<String>,
+}
 
-    render_markdown(
-        (post.events)(),
-        &mut slug_counts,
-        post.id,
-        &mut image_index,
-        highlighter,
-    )
+pub struct Heading {
+    pub level: u8,
+    pub text: String,
+    pub slug: String,
 }
 
-fn render_markdown<'a, I>(
-    events: I,
-    slug_counts: &mut HashMap<String, usize>,
-    post_id: &str,
-    image_index: &mut usize,
-    highlighter: &Highlighter,
-) -> Markup
+/// State shared by every frame of a post while it renders, including callout fragments.
+struct RenderContext<'a> {
+    post_id: &'a str,
+    highlighter: &'a Highlighter,
+    slug_counts: HashMap<String, usize>,
+    image_index: usize,
+    first_image: Option<String>,
+    headings: Vec<Heading>,
+    footnotes: Footnotes,
+    /// Rendering a listing excerpt, which drops headings, images and footnote references.
+    excerpt: bool,
+}
+
+/// Footnotes are numbered in the order they are first referenced. Definitions are held back
+/// until the whole post has rendered, since they may appear before their references and
+/// each back-link needs to know how often its footnote was cited.
+#[derive(Default)]
+struct Footnotes {
+    numbers: HashMap<String, usize>,
+    reference_counts: Vec<usize>,
+    definitions: HashMap<String, Vec<RenderNode>>,
+}
+
+impl Footnotes {
+    /// Returns the footnote's number and the 1-based index of this reference to it.
+    fn reference(&mut self, label: &str) -> (usize, usize) {
+        let next = self.numbers.len() + 1;
+        let number = *self.numbers.entry(label.to_string()).or_insert(next);
+        if number > self.reference_counts.len() {
+            self.reference_counts.push(0);
+        }
+        self.reference_counts[number - 1] += 1;
+        (number, self.reference_counts[number - 1])
+    }
+}
+
+impl<'a> RenderContext<'a> {
+    fn new(post_id: &'a str, highlighter: &'a Highlighter) -> Self {
+        Self {
+            post_id,
+            highlighter,
+            slug_counts: HashMap::new(),
+            image_index: 0,
+            first_image: None,
+            headings: Vec::new(),
+            footnotes: Footnotes::default(),
+            excerpt: false,
+        }
+    }
+}
+
+pub fn render_post(post: &Post, highlighter: &Highlighter) -> RenderedPost {
+    let mut ctx = RenderContext::new(post.id, highlighter);
+    let body = render_markdown(post.events(), &mut ctx);
+    let footnotes = render_footnotes(&mut ctx);
+
+    RenderedPost {
+        body: html! {
+            (body)
+            (footnotes)
+        },
+        headings: ctx.headings,
+        summary: first_paragraph(post.events()),
+        first_image: ctx.first_image,
+    }
+}
+
+/// The teaser shown under a post on the listings: its `description`, or else the markdown
+/// before `<!-- more -->`. Inline formatting is kept, headings, images and footnote references
+/// are left out. Posts with neither are listed by title alone.
+pub fn render_excerpt(post: &Post, highlighter: &Highlighter) -> Option<Markup> {
+    let markdown = match post.meta.description {
+        Some(description) => description,
+        None => post.markdown.split_once(MORE_MARKER)?.0,
+    };
+    let mut ctx = RenderContext::new(post.id, highlighter);
+    ctx.excerpt = true;
+    Some(render_markdown_fragment(markdown, &mut ctx))
+}
+
+/// Plain text of the first top-level paragraph with any text in it, cut at a word boundary.
+/// Image alt text is skipped so a paragraph holding only an image doesn't count.
+fn first_paragraph<'a>(events: impl IntoIterator<Item = Event<'a>>) -> Option<String> {
+    let mut depth = 0usize;
+    let mut in_paragraph = false;
+    let mut in_image = false;
+    let mut text = String::new();
+    for event in events {
+        match event {
+            Event::Start(tag) => {
+                in_paragraph |= depth == 0 && matches!(tag, Tag::Paragraph);
+                in_image |= matches!(tag, Tag::Image { .. });
+                depth += 1;
+            }
+            Event::End(tag) => {
+                depth -= 1;
+                in_image &= tag != TagEnd::Image;
+                if in_paragraph && depth == 0 {
+                    let summary = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                    if !summary.is_empty() {
+                        return Some(shorten(&summary, SUMMARY_LENGTH));
+                    }
+                    in_paragraph = false;
+                    text.clear();
+                }
+            }
+            Event::Text(value) | Event::Code(value) | Event::InlineMath(value)
+                if in_paragraph && !in_image =>
+            {
+                text.push_str(&value)
+            }
+            Event::SoftBreak | Event::HardBreak if in_paragraph => text.push(' '),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn shorten(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let cut = text
+        .char_indices()
+        .nth(max_chars)
+        .map_or(text.len(), |(idx, _)| idx);
+    let head = &text[..cut];
+    let head = head.rfind(' ').map_or(head, |space| &head[..space]);
+    format!("{}…", head.trim_end_matches([',', ';', ':', '.']))
+}
+
+fn render_markdown<'a, I>(events: I, ctx: &mut RenderContext) -> Markup
 where
     I: IntoIterator<Item = Event<'a>>,
 {
@@ -120,16 +259,18 @@ where
     for event in events {
         match event {
             Event::Start(tag) => handle_start_event(tag, &mut frames),
-            Event::End(_) => {
-                handle_end_event(&mut frames, slug_counts, post_id, image_index, highlighter)
-            }
+            Event::End(_) => handle_end_event(&mut frames, ctx),
             Event::Text(text) => handle_text_event(text, &mut frames),
             Event::Code(code) => handle_code_event(code, &mut frames),
-            Event::InlineMath(text) => handle_inline_math_event(text, &mut frames),
-            Event::DisplayMath(text) => handle_display_math_event(text, &mut frames),
+            Event::InlineMath(text) => handle_math_event(text, false, &mut frames, ctx),
+            Event::DisplayMath(text) => handle_math_event(text, true, &mut frames, ctx),
             Event::Html(raw) => handle_html_event(raw, &mut frames),
             Event::InlineHtml(raw) => handle_inline_html_event(raw, &mut frames),
-            Event::FootnoteReference(label) => handle_footnote_reference_event(label, &mut frames),
+            // The excerpt's footnotes are never listed, so its references would link nowhere.
+            Event::FootnoteReference(_) if ctx.excerpt => {}
+            Event::FootnoteReference(label) => {
+                handle_footnote_reference_event(label, &mut frames, ctx)
+            }
             Event::SoftBreak => handle_soft_break_event(&mut frames),
             Event::HardBreak => handle_hard_break_event(&mut frames),
             Event::Rule => handle_rule_event(&mut frames),
@@ -140,22 +281,13 @@ where
     let root = frames
         .pop()
         .expect("render_post should always keep a root frame");
-    render_nodes(&root.buffer, highlighter)
+    render_nodes(&root.buffer, ctx.highlighter)
 }
 
-fn render_markdown_fragment(
-    markdown: &str,
-    slug_counts: &mut HashMap<String, usize>,
-    post_id: &str,
-    image_index: &mut usize,
-    highlighter: &Highlighter,
-) -> Markup {
+fn render_markdown_fragment(markdown: &str, ctx: &mut RenderContext) -> Markup {
     render_markdown(
-        TextMergeStream::new(Parser::new_ext(markdown, Options::ENABLE_TABLES)),
-        slug_counts,
-        post_id,
-        image_index,
-        highlighter,
+        TextMergeStream::new(Parser::new_ext(markdown, MARKDOWN_OPTIONS)),
+        ctx,
     )
 }
 
@@ -203,6 +335,7 @@ fn handle_start_event(tag: Tag, frames: &mut Vec<Frame>) {
                 FrameKind::TableCell
             }
         }
+        Tag::FootnoteDefinition(label) => FrameKind::FootnoteDefinition(label.to_string()),
         _ => FrameKind::Root,
     };
 
@@ -213,19 +346,13 @@ fn handle_start_event(tag: Tag, frames: &mut Vec<Frame>) {
     });
 }
 
-fn handle_end_event(
-    frames: &mut Vec<Frame>,
-    slug_counts: &mut HashMap<String, usize>,
-    post_id: &str,
-    image_index: &mut usize,
-    highlighter: &Highlighter,
-) {
+fn handle_end_event(frames: &mut Vec<Frame>, ctx: &mut RenderContext) {
     if frames.len() <= 1 {
         return;
     }
 
     let frame = frames.pop().expect("frame stack underflow");
-    let rendered = render_frame(frame, slug_counts, post_id, image_index, highlighter);
+    let rendered = render_frame(frame, ctx);
     append_node(rendered, frames);
 }
 
@@ -270,19 +397,18 @@ fn handle_code_event(code: CowStr, frames: &mut [Frame]) {
     );
 }
 
-fn handle_inline_math_event(text: CowStr, frames: &mut [Frame]) {
-    append_markup(
-        html! {
-            span { (text.as_ref()) }
-        },
-        frames,
-    );
-}
-
-fn handle_display_math_event(text: CowStr, frames: &mut [Frame]) {
+fn handle_math_event(text: CowStr, display: bool, frames: &mut [Frame], ctx: &RenderContext) {
+    let math = math::render_math(text.as_ref(), display).unwrap_or_else(|err| {
+        log::warn!("post {}: cannot typeset `{}`: {}", ctx.post_id, text, err);
+        math::render_math_error(text.as_ref(), display, &err)
+    });
     append_markup(
         html! {
-            div { (text.as_ref()) }
+            @if display {
+                div class="my-6 overflow-x-auto overflow-y-hidden py-1 text-[1.1em] text-gray-100" { (math) }
+            } @else {
+                (math)
+            }
         },
         frames,
     );
@@ -306,10 +432,18 @@ fn handle_inline_html_event(raw: CowStr, frames: &mut [Frame]) {
     );
 }
 
-fn handle_footnote_reference_event(label: CowStr, frames: &mut [Frame]) {
+fn handle_footnote_reference_event(label: CowStr, frames: &mut [Frame], ctx: &mut RenderContext) {
+    let (number, index) = ctx.footnotes.reference(label.as_ref());
     append_markup(
         html! {
-            sup { (label.as_ref()) }
+            sup id=(footnote_ref_id(number, index)) class="ml-0.5" {
+                a   class="border-b-0 text-sky-300 hover:text-white"
+                    href={ "#fn-" (number) }
+                    aria-label={ "Footnote " (number) }
+                {
+                    (number)
+                }
+            }
         },
         frames,
     );
@@ -419,39 +553,34 @@ fn render_nodes(buffer: &[RenderNode], highlighter: &Highlighter) -> Markup {
     }
 }
 
-fn render_frame(
-    frame: Frame,
-    slug_counts: &mut HashMap<String, usize>,
-    post_id: &str,
-    image_index: &mut usize,
-    highlighter: &Highlighter,
-) -> RenderNode {
+fn render_frame(frame: Frame, ctx: &mut RenderContext) -> RenderNode {
+    let highlighter = ctx.highlighter;
     match frame.kind {
         FrameKind::Root => RenderNode::Markup(render_nodes(&frame.buffer, highlighter)),
         FrameKind::Paragraph => RenderNode::Paragraph {
             content: render_nodes(&frame.buffer, highlighter).into_string(),
         },
+        FrameKind::Heading(_) | FrameKind::Image { .. } if ctx.excerpt => {
+            RenderNode::Markup(html! {})
+        }
         FrameKind::Heading(level) => match level {
             HeadingLevel::H1 => render_heading(
                 "h1",
                 "text-4xl md:text-5xl font-semibold tracking-tight text-white mt-10 mb-6 scroll-mt-24",
                 &frame,
-                slug_counts,
-                highlighter,
+                ctx,
             ),
             HeadingLevel::H2 => render_heading(
                 "h2",
                 "text-2xl md:text-3xl font-semibold tracking-tight text-white mt-10 mb-4 scroll-mt-24",
                 &frame,
-                slug_counts,
-                highlighter,
+                ctx,
             ),
             _ => render_heading(
                 "h3",
                 "text-xl md:text-2xl font-semibold text-white mt-8 mb-3 scroll-mt-24",
                 &frame,
-                slug_counts,
-                highlighter,
+                ctx,
             ),
         },
         FrameKind::BlockQuote => RenderNode::BlockQuote {
@@ -459,14 +588,7 @@ fn render_frame(
         },
         FrameKind::CodeBlock { info, text } => {
             if let Some(kind) = CalloutKind::from_code_block(&info) {
-                RenderNode::Markup(render_callout(
-                    kind,
-                    &text,
-                    slug_counts,
-                    post_id,
-                    image_index,
-                    highlighter,
-                ))
+                RenderNode::Markup(render_callout(kind, &text, ctx))
             } else {
                 RenderNode::CodeBlock { info, text }
             }
@@ -530,7 +652,8 @@ fn render_frame(
             title,
             alt,
         } => {
-            let dest_url = resolve_image_src(&dest_url, post_id, image_index);
+            let dest_url = resolve_image_src(&dest_url, ctx);
+            ctx.first_image.get_or_insert_with(|| dest_url.clone());
             RenderNode::Markup(html! {
                 figure class="flex flex-col items-center my-6" {
                     @if title.is_empty() {
@@ -574,6 +697,81 @@ fn render_frame(
                 (render_nodes(&frame.buffer, highlighter))
             }
         }),
+        FrameKind::FootnoteDefinition(label) => {
+            ctx.footnotes.definitions.insert(label, frame.buffer);
+            RenderNode::Markup(html! {})
+        }
+    }
+}
+
+/// The footnotes section closing the post. Definitions that are never referenced are
+/// dropped, references without a definition are left pointing nowhere.
+fn render_footnotes(ctx: &mut RenderContext) -> Markup {
+    let mut numbered = ctx
+        .footnotes
+        .numbers
+        .iter()
+        .filter_map(|(label, number)| {
+            let nodes = ctx.footnotes.definitions.remove(label)?;
+            Some((*number, nodes))
+        })
+        .collect::<Vec<_>>();
+    if numbered.is_empty() {
+        return html! {};
+    }
+    numbered.sort_by_key(|(number, _)| *number);
+
+    let items = numbered.into_iter().map(|(number, mut nodes)| {
+        let back_links = footnote_back_links(number, ctx.footnotes.reference_counts[number - 1]);
+        // Keep the back-links on the last line of text rather than on a line of their own.
+        if let Some(RenderNode::Paragraph { content }) = nodes.last_mut() {
+            content.push_str(&back_links.into_string());
+            (number, render_nodes(&nodes, ctx.highlighter))
+        } else {
+            (
+                number,
+                html! {
+                    (render_nodes(&nodes, ctx.highlighter))
+                    (back_links)
+                },
+            )
+        }
+    });
+
+    html! {
+        section class="mt-12 border-t border-white/10 pt-6 text-sm" aria-label="Footnotes" {
+            ol class="list-decimal pl-6 space-y-2 text-gray-400 marker:text-gray-500" {
+                @for (number, content) in items {
+                    li id={ "fn-" (number) } class="scroll-mt-24 [&_p]:text-gray-400 [&_p]:mt-2" {
+                        (content)
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// One "↩" per reference, so readers can jump back to whichever citation they came from.
+fn footnote_back_links(number: usize, references: usize) -> Markup {
+    html! {
+        @for index in 1..=references {
+            " "
+            a   class="border-b-0 text-sky-300 hover:text-white"
+                href={ "#" (footnote_ref_id(number, index)) }
+                aria-label={ "Back to reference " (number) }
+            {
+                "↩"
+                @if references > 1 { sup { (index) } }
+            }
+        }
+    }
+}
+
+fn footnote_ref_id(number: usize, index: usize) -> String {
+    if index == 1 {
+        format!("fnref-{}", number)
+    } else {
+        format!("fnref-{}-{}", number, index)
     }
 }
 
@@ -587,14 +785,13 @@ fn append_heading_text(frames: &mut [Frame], text: &str) {
     }
 }
 
-fn render_heading(
-    tag: &str,
-    classes: &str,
-    frame: &Frame,
-    slug_counts: &mut HashMap<String, usize>,
-    highlighter: &Highlighter,
-) -> RenderNode {
-    let slug = unique_slug(&frame.text, slug_counts);
+fn render_heading(tag: &str, classes: &str, frame: &Frame, ctx: &mut RenderContext) -> RenderNode {
+    let slug = unique_slug(&frame.text, &mut ctx.slug_counts);
+    ctx.headings.push(Heading {
+        level: tag[1..].parse().unwrap_or(3),
+        text: frame.text.clone(),
+        slug: slug.clone(),
+    });
     let anchor = html! {
         a   class="inline-flex items-center text-white/40 hover:text-white/70 text-base align-middle no-underline border-b-0 opacity-0 group-hover:opacity-100 focus:opacity-100 focus-visible:opacity-100 transition-opacity translate-y-1"
             href={ "#" (slug) }
@@ -603,7 +800,7 @@ fn render_heading(
             (PreEscaped(icons::LINK))
         }
     };
-    let content = render_nodes(&frame.buffer, highlighter);
+    let content = render_nodes(&frame.buffer, ctx.highlighter);
     let heading_classes = format!("{} group flex items-baseline gap-3", classes);
     RenderNode::Markup(match tag {
         "h1" => html! {
@@ -658,7 +855,7 @@ fn slugify(text: &str) -> String {
     slug.trim_matches('-').to_string()
 }
 
-fn resolve_image_src(dest_url: &str, post_id: &str, image_index: &mut usize) -> String {
+fn resolve_image_src(dest_url: &str, ctx: &mut RenderContext) -> String {
     if !is_local_image(dest_url) {
         return dest_url.to_string();
     }
@@ -666,8 +863,8 @@ fn resolve_image_src(dest_url: &str, post_id: &str, image_index: &mut usize) ->
         .extension()
         .and_then(|ext| ext.to_str())
         .expect("local image URLs must include a file extension");
-    *image_index += 1;
-    format!("/img/{}/{}.{}", post_id, *image_index, extension)
+    ctx.image_index += 1;
+    format!("/img/{}/{}.{}", ctx.post_id, ctx.image_index, extension)
 }
 
 fn is_local_image(dest_url: &str) -> bool {
@@ -684,15 +881,8 @@ fn is_local_image(dest_url: &str) -> bool {
     !dest_url.contains("://")
 }
 
-fn render_callout(
-    kind: CalloutKind,
-    text: &str,
-    slug_counts: &mut HashMap<String, usize>,
-    post_id: &str,
-    image_index: &mut usize,
-    highlighter: &Highlighter,
-) -> Markup {
-    let content = render_markdown_fragment(text, slug_counts, post_id, image_index, highlighter);
+fn render_callout(kind: CalloutKind, text: &str, ctx: &mut RenderContext) -> Markup {
+    let content = render_markdown_fragment(text, ctx);
 
     html! {
         aside class=(kind.panel_classes()) {
@@ -842,26 +1032,104 @@ fn code_language(info: &Option<String>) -> Option<&str> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::content::post::types::PostMeta;
 
     fn render(markdown: &str) -> String {
         let highlighter = Highlighter::default();
-        let mut slug_counts = HashMap::new();
-        let mut image_index = 0usize;
+        let mut ctx = RenderContext::new("test-post", &highlighter);
 
-        render_markdown_fragment(
-            markdown,
-            &mut slug_counts,
-            "test-post",
-            &mut image_index,
-            &highlighter,
-        )
-        .into_string()
+        render_markdown_fragment(markdown, &mut ctx).into_string()
     }
 
     fn count_matches(haystack: &str, needle: &str) -> usize {
         haystack.matches(needle).count()
     }
 
+    #[test]
+    fn collects_headings_with_unique_slugs() {
+        let highlighter = Highlighter::default();
+        let mut ctx = RenderContext::new("test-post", &highlighter);
+        render_markdown_fragment("# Intro\n\n## Setup `cargo`\n\n#### Intro", &mut ctx);
+
+        let headings = ctx
+            .headings
+            .iter()
+            .map(|heading| (heading.level, heading.text.as_str(), heading.slug.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            headings,
+            [
+                (1, "Intro", "intro"),
+                (2, "Setup cargo", "setup-cargo"),
+                (3, "Intro", "intro-2"),
+            ]
+        );
+    }
+
+    fn summary(markdown: &str) -> Option<String> {
+        first_paragraph(Parser::new_ext(markdown, MARKDOWN_OPTIONS))
+    }
+
+    #[test]
+    fn summarizes_the_first_top_level_paragraph() {
+        assert_eq!(
+            summary(
+                "# Intro\n\n![Chart](chart.svg)\n\n> Quoted\n\nThe `boxpin` crate,\nexplained.\n\nLater."
+            ),
+            Some("The boxpin crate, explained.".to_string())
+        );
+        assert_eq!(summary("```rust\nfn main() {}\n```"), None);
+
+        let long = "word ".repeat(100);
+        let shortened = summary(&long).unwrap();
+        assert!(shortened.ends_with("word…"));
+        assert!(shortened.chars().count() <= SUMMARY_LENGTH + 1);
+    }
+
+    #[test]
+    fn records_the_first_image_url() {
+        let highlighter = Highlighter::default();
+        let mut ctx = RenderContext::new("test-post", &highlighter);
+        render_markdown_fragment(
+            "Text\n\n![First](chart.svg)\n\n![Second](https://example.com/b.png)",
+            &mut ctx,
+        );
+
+        assert_eq!(ctx.first_image.as_deref(), Some("/img/test-post/1.svg"));
+    }
+
+    fn render_with_footnotes(markdown: &str) -> String {
+        let highlighter = Highlighter::default();
+        let mut ctx = RenderContext::new("test-post", &highlighter);
+        let body = render_markdown_fragment(markdown, &mut ctx);
+
+        html! { (body) (render_footnotes(&mut ctx)) }.into_string()
+    }
+
+    #[test]
+    fn numbers_footnotes_by_first_reference() {
+        let html = render_with_footnotes(
+            "[^b]: Second.\n\nCited[^a] and[^b] again[^a].\n\n[^a]: First.\n\n[^unused]: Never cited.",
+        );
+
+        assert!(html.contains("<sup id=\"fnref-1\""));
+        assert!(html.contains("<sup id=\"fnref-2\""));
+        assert!(html.contains("<sup id=\"fnref-1-2\""));
+        assert!(html.find("id=\"fn-1\"").unwrap() < html.find("First.").unwrap());
+        assert!(html.find("First.").unwrap() < html.find("Second.").unwrap());
+        assert!(html.contains("href=\"#fnref-1-2\""));
+        assert!(!html.contains("Never cited."));
+    }
+
+    #[test]
+    fn keeps_footnote_back_links_inside_the_last_paragraph() {
+        let html = render_with_footnotes("Cited[^1].\n\n[^1]: See the benchmarks.");
+
+        assert!(html.contains("See the benchmarks. <a"));
+        assert!(html.contains("href=\"#fnref-1\""));
+        assert!(html.contains("↩</a></p>"));
+    }
+
     #[test]
     fn renders_styled_blockquotes() {
         let html = render("> Quoted line");
@@ -974,4 +1242,34 @@ mod tests {
         assert_eq!(count_matches(&html, "code-copy-btn"), 2);
         assert_eq!(count_matches(&html, "<aside"), 1);
     }
+
+    #[test]
+    fn renders_excerpts_from_description_or_more_marker() {
+        let highlighter = Highlighter::default();
+        let mut post = Post {
+            id: "test-post",
+            meta: PostMeta {
+                title: "Test",
+                ..PostMeta::TEST
+            },
+            markdown: "## Setup\n\nIntro with *emphasis*[^1] ![chart](1.png)\n\n[^1]: A footnote.\n\n<!-- more -->\n\nRest of the post",
+        };
+
+        let excerpt = render_excerpt(&post, &highlighter).unwrap().into_string();
+        assert!(excerpt.contains("Intro with <em>emphasis</em>"));
+        assert!(!excerpt.contains("Setup"));
+        assert!(!excerpt.contains("<img"));
+        assert!(!excerpt.contains("<sup"));
+        assert!(!excerpt.contains("A footnote"));
+        assert!(!excerpt.contains("Rest of the post"));
+
+        post.meta.description = Some("A `description` wins");
+        let excerpt = render_excerpt(&post, &highlighter).unwrap().into_string();
+        assert!(excerpt.contains("<code"));
+        assert!(!excerpt.contains("Intro"));
+
+        post.meta.description = None;
+        post.markdown = "No marker here";
+        assert!(render_excerpt(&post, &highlighter).is_none());
+    }
 }
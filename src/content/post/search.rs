@@ -0,0 +1,299 @@
+use maud::{Markup, html};
+use pulldown_cmark::{Event, Tag, TagEnd};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Bound,
+};
+
+use super::types::Post;
+
+const TITLE_WEIGHT: f32 = 4.0;
+const HEADING_WEIGHT: f32 = 2.0;
+const BODY_WEIGHT: f32 = 1.0;
+const CODE_WEIGHT: f32 = 0.25;
+
+const MAX_QUERY_TERMS: usize = 8;
+const SNIPPET_WORDS_BEFORE: usize = 12;
+const SNIPPET_WORDS: usize = 36;
+
+/// Inverted index over every published post, built once at startup.
+pub struct SearchIndex {
+    documents: Vec<Document>,
+    postings: BTreeMap<String, Vec<Posting>>,
+}
+
+struct Document {
+    post: &'static Post,
+    /// Prose with markup and code blocks stripped, used to cut result snippets.
+    text: String,
+}
+
+struct Posting {
+    document: usize,
+    weight: f32,
+}
+
+pub struct SearchHit {
+    pub post: &'static Post,
+    pub snippet: Markup,
+}
+
+impl SearchIndex {
+    pub fn new(posts: &[&'static Post]) -> Self {
+        let mut documents = Vec::with_capacity(posts.len());
+        let mut postings: BTreeMap<String, Vec<Posting>> = BTreeMap::new();
+
+        for (document, post) in posts.iter().copied().enumerate() {
+            let (weights, text) = term_weights(post);
+            for (term, weight) in weights {
+                postings
+                    .entry(term)
+                    .or_default()
+                    .push(Posting { document, weight });
+            }
+            documents.push(Document { post, text });
+        }
+
+        Self {
+            documents,
+            postings,
+        }
+    }
+
+    /// Ranks posts containing every query term; the last term also matches as a prefix so
+    /// results show up while a word is still being typed.
+    pub fn search(&self, query: &str) -> Vec<SearchHit> {
+        let terms = tokenize(query).take(MAX_QUERY_TERMS).collect::<Vec<_>>();
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let mut scores: HashMap<usize, (f32, usize)> = HashMap::new();
+        for (idx, term) in terms.iter().enumerate() {
+            let prefix = idx == terms.len() - 1;
+            let mut term_weights: HashMap<usize, f32> = HashMap::new();
+            for posting in self.matching_postings(term, prefix) {
+                *term_weights.entry(posting.document).or_insert(0.0) += posting.weight;
+            }
+            let idf = (1.0 + self.documents.len() as f32 / term_weights.len().max(1) as f32).ln();
+            for (document, weight) in term_weights {
+                let entry = scores.entry(document).or_insert((0.0, 0));
+                entry.0 += (1.0 + weight).ln() * idf;
+                entry.1 += 1;
+            }
+        }
+
+        let mut ranked = scores
+            .into_iter()
+            .filter(|(_, (_, matched))| *matched == terms.len())
+            .map(|(document, (score, _))| (document, score))
+            .collect::<Vec<_>>();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        ranked
+            .into_iter()
+            .map(|(document, _)| {
+                let document = &self.documents[document];
+                SearchHit {
+                    post: document.post,
+                    snippet: snippet(&document.text, &terms),
+                }
+            })
+            .collect()
+    }
+
+    fn matching_postings<'a>(
+        &'a self,
+        term: &'a str,
+        prefix: bool,
+    ) -> impl Iterator<Item = &'a Posting> + 'a {
+        self.postings
+            .range::<str, _>((Bound::Included(term), Bound::Unbounded))
+            .take_while(move |(key, _)| key.starts_with(term))
+            .filter(move |(key, _)| prefix || key.as_str() == term)
+            .flat_map(|(_, list)| list)
+    }
+}
+
+/// Terms of the post's title, headings, prose and code, each weighted by where it appears,
+/// plus the prose as plain text.
+pub(super) fn term_weights(post: &Post) -> (HashMap<String, f32>, String) {
+    let mut weights: HashMap<String, f32> = HashMap::new();
+    let mut add = |text: &str, weight: f32| {
+        for term in tokenize(text) {
+            *weights.entry(term).or_insert(0.0) += weight;
+        }
+    };
+
+    add(post.meta.title, TITLE_WEIGHT);
+    let text = extract_text(post, &mut add);
+    (weights, text)
+}
+
+/// Feeds headings, prose and code blocks to `add` with their respective weights and returns
+/// the prose as plain text.
+fn extract_text(post: &Post, add: &mut impl FnMut(&str, f32)) -> String {
+    let mut text = String::new();
+    let mut in_heading = false;
+    let mut in_code_block = false;
+
+    for event in post.events() {
+        match event {
+            Event::Start(Tag::Heading { .. }) => in_heading = true,
+            Event::End(TagEnd::Heading(_)) => {
+                in_heading = false;
+                text.push(' ');
+            }
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(TagEnd::CodeBlock) => in_code_block = false,
+            Event::End(TagEnd::Paragraph | TagEnd::Item | TagEnd::TableCell) => text.push(' '),
+            Event::SoftBreak | Event::HardBreak => text.push(' '),
+            Event::Text(value) | Event::Code(value) => {
+                if in_code_block {
+                    add(&value, CODE_WEIGHT);
+                } else if in_heading {
+                    add(&value, HEADING_WEIGHT);
+                    text.push_str(&value);
+                } else {
+                    add(&value, BODY_WEIGHT);
+                    text.push_str(&value);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    text
+}
+
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    words(text)
+        .map(|(_, word)| word.to_lowercase())
+        .filter(|word| word.chars().count() >= 2)
+}
+
+/// Maximal alphanumeric runs of `text` with their byte offsets.
+fn words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(move |word| (word.as_ptr() as usize - text.as_ptr() as usize, word))
+}
+
+fn snippet(text: &str, terms: &[String]) -> Markup {
+    let words = words(text).collect::<Vec<_>>();
+    // Same rule as the lookup: every term matches whole words, the last one also prefixes.
+    let is_match = |word: &str| {
+        let word = word.to_lowercase();
+        terms.iter().enumerate().any(|(idx, term)| {
+            word == *term || (idx == terms.len() - 1 && word.starts_with(term.as_str()))
+        })
+    };
+
+    let first_match = words.iter().position(|(_, word)| is_match(word));
+    let start = first_match.map_or(0, |idx| idx.saturating_sub(SNIPPET_WORDS_BEFORE));
+    let end = (start + SNIPPET_WORDS).min(words.len());
+    if start >= end {
+        return html! {};
+    }
+
+    let mut segments = Vec::with_capacity(end - start);
+    let mut cursor = words[start].0;
+    for (offset, word) in &words[start..end] {
+        segments.push((&text[cursor..*offset], *word, is_match(word)));
+        cursor = offset + word.len();
+    }
+
+    html! {
+        @if start > 0 { "… " }
+        @for (gap, word, matched) in segments {
+            (gap)
+            @if matched {
+                mark class="rounded bg-amber-300/20 px-0.5 text-white" { (word) }
+            } @else {
+                (word)
+            }
+        }
+        @if end < words.len() { " …" }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::post::types::{Date, PostMeta};
+
+    const DATE: Date = Date {
+        year: 2026,
+        month: 1,
+        day: 1,
+        time: None,
+    };
+
+    const BLOOM: Post = Post {
+        id: "bloom",
+        meta: PostMeta {
+            title: "Bloom filters",
+            published: DATE,
+            ..PostMeta::TEST
+        },
+        markdown: "## Hashing\n\nA probabilistic set with false positives.\n\n```rust\nlet hash = 1;\n```",
+    };
+
+    const SATA: Post = Post {
+        id: "sata",
+        meta: PostMeta {
+            title: "SATA controllers",
+            published: DATE,
+            ..PostMeta::TEST
+        },
+        markdown: "My NAS kept dropping disks. Not a bloom filter problem.",
+    };
+
+    fn ids(hits: &[SearchHit]) -> Vec<&str> {
+        hits.iter().map(|hit| hit.post.id).collect()
+    }
+
+    #[test]
+    fn ranks_title_matches_above_body_matches() {
+        let index = SearchIndex::new(&[&SATA, &BLOOM]);
+
+        assert_eq!(ids(&index.search("bloom")), ["bloom", "sata"]);
+    }
+
+    #[test]
+    fn requires_every_term_and_matches_last_term_as_prefix() {
+        let index = SearchIndex::new(&[&SATA, &BLOOM]);
+
+        assert_eq!(ids(&index.search("false posit")), ["bloom"]);
+        assert_eq!(ids(&index.search("nas posit")), Vec::<&str>::new());
+        assert!(index.search("  ").is_empty());
+    }
+
+    #[test]
+    fn indexes_code_blocks_without_quoting_them_in_snippets() {
+        let index = SearchIndex::new(&[&SATA, &BLOOM]);
+        let hits = index.search("hash");
+
+        assert_eq!(ids(&hits), ["bloom"]);
+        assert!(!hits[0].snippet.0.contains("let"));
+    }
+
+    #[test]
+    fn highlights_matching_words_in_snippets() {
+        let index = SearchIndex::new(&[&SATA, &BLOOM]);
+        let hits = index.search("disks");
+
+        assert!(hits[0].snippet.0.contains(">disks</mark>"));
+        assert!(hits[0].snippet.0.starts_with("My NAS kept dropping"));
+    }
+
+    #[test]
+    fn highlights_only_the_last_term_as_a_prefix() {
+        let terms = ["filter".to_string(), "ke".to_string()];
+        let snippet = snippet("Bloom filters filter keys", &terms).0;
+
+        assert!(snippet.contains(">filter</mark>"));
+        assert!(snippet.contains(">keys</mark>"));
+        assert!(!snippet.contains(">filters</mark>"));
+    }
+}
@@ -0,0 +1,140 @@
+use axum::{extract::State, http::HeaderMap, response::Response};
+use maud::{PreEscaped, html};
+use std::sync::Arc;
+
+use super::types::Post;
+use crate::state::{AppState, Site};
+
+const XML_DECLARATION: &str = r#"<?xml version="1.0" encoding="utf-8"?>"#;
+
+/// The listing pages plus every post. `published_posts` keeps drafts in debug builds so they
+/// can be previewed locally; they are filtered out again here so a sitemap generated from a
+/// debug build never points crawlers at them.
+pub fn render_sitemap(site: &Site, posts: &[&Post]) -> String {
+    let posts = posts
+        .iter()
+        .filter(|post| !post.meta.draft)
+        .collect::<Vec<_>>();
+    let latest = posts
+        .iter()
+        .map(|post| post.meta.last_modified())
+        .max()
+        .map(|date| date.to_w3c_datetime());
+    let pages = [
+        ("/", latest.clone()),
+        ("/posts", latest.clone()),
+        ("/archive", latest),
+        ("/about", None),
+    ];
+    let sitemap = html! {
+        (PreEscaped(XML_DECLARATION))
+        urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" {
+            @for (path, lastmod) in pages {
+                url {
+                    loc { (site.url(path)) }
+                    @if let Some(lastmod) = lastmod {
+                        lastmod { (lastmod) }
+                    }
+                }
+            }
+            @for post in posts {
+                url {
+                    loc { (site.url(&format!("/post/{}", post.id))) }
+                    lastmod { (post.meta.last_modified().to_w3c_datetime()) }
+                }
+            }
+        }
+    };
+    sitemap.into_string()
+}
+
+pub fn render_robots(site: &Site) -> String {
+    let mut robots = String::from("User-agent: *\n");
+    if site.robots.disallow.is_empty() {
+        robots.push_str("Disallow:\n");
+    }
+    for path in &site.robots.disallow {
+        robots.push_str(&format!("Disallow: {}\n", path));
+    }
+    robots.push_str(&format!("\nSitemap: {}\n", site.url("/sitemap.xml")));
+    robots
+}
+
+pub async fn get_sitemap(State(state): State<Arc<AppState>>, headers: HeaderMap) -> Response {
+    state
+        .post_state()
+        .sitemap()
+        .respond(&headers, "application/xml; charset=utf-8")
+}
+
+pub async fn get_robots(State(state): State<Arc<AppState>>, headers: HeaderMap) -> Response {
+    state
+        .post_state()
+        .robots()
+        .respond(&headers, "text/plain; charset=utf-8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::post::types::{Date, PostMeta};
+
+    const fn post(id: &'static str, day: u8, draft: bool) -> Post {
+        Post {
+            id,
+            meta: PostMeta {
+                title: "Bloom filters",
+                published: Date {
+                    year: 2025,
+                    month: 1,
+                    day,
+                    time: None,
+                },
+                draft,
+                ..PostMeta::TEST
+            },
+            markdown: "",
+        }
+    }
+
+    fn site(robots: &str) -> Site {
+        Site::parse(&format!(
+            r#"
+            title = "Hut"
+            author = "Someone"
+            base_url = "https://example.com"
+            {}
+            "#,
+            robots
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn lists_pages_and_published_posts_only() {
+        const PUBLISHED: Post = post("bloom", 9, false);
+        const DRAFT: Post = post("unfinished", 12, true);
+        let sitemap = render_sitemap(&site(""), &[&DRAFT, &PUBLISHED]);
+
+        assert!(sitemap.contains("<loc>https://example.com/</loc><lastmod>2025-01-09</lastmod>"));
+        assert!(sitemap.contains("<loc>https://example.com/about</loc></url>"));
+        assert!(
+            sitemap
+                .contains("<loc>https://example.com/post/bloom</loc><lastmod>2025-01-09</lastmod>")
+        );
+        assert!(!sitemap.contains("unfinished"));
+        assert!(!sitemap.contains("2025-01-12"));
+    }
+
+    #[test]
+    fn renders_configured_robots_rules() {
+        assert_eq!(
+            render_robots(&site("")),
+            "User-agent: *\nDisallow:\n\nSitemap: https://example.com/sitemap.xml\n"
+        );
+        assert_eq!(
+            render_robots(&site("[robots]\ndisallow = [\"/search\", \"/tags/\"]")),
+            "User-agent: *\nDisallow: /search\nDisallow: /tags/\n\nSitemap: https://example.com/sitemap.xml\n"
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use std::path::Path;
+
+use super::types::{Date, Post, PostMeta, Time};
+
+/// Runtime counterpart of the `Post` derive: parses `content/post` from the source checkout.
+///
+/// Posts are leaked to keep the `&'static Post` shape the rest of the crate works with. Only
+/// `hot-reload` builds call this, once per content change, so the leak stays small.
+pub fn load_posts() -> Result<Vec<&'static Post>, String> {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let posts = loader::load_posts(manifest_dir).map_err(|errors| {
+        errors
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("\n")
+    })?;
+
+    Ok(posts
+        .into_iter()
+        .map(|post| {
+            let tags = post.tags.into_iter().map(leak).collect::<Vec<_>>();
+            let post = Post {
+                id: leak(post.id),
+                meta: PostMeta {
+                    title: leak(post.title),
+                    published: date(post.published),
+                    updated: post.updated.map(date),
+                    draft: post.draft,
+                    weight: post.weight,
+                    tags: Box::leak(tags.into_boxed_slice()),
+                    toc: post.toc,
+                    description: post.description.map(leak),
+                    image: post.image.map(leak),
+                },
+                markdown: leak(post.markdown),
+            };
+            &*Box::leak(Box::new(post))
+        })
+        .collect())
+}
+
+fn date(date: loader::PostDate) -> Date {
+    Date {
+        year: date.year,
+        month: date.month,
+        day: date.day,
+        time: date.time.map(|time| Time {
+            hour: time.hour,
+            minute: time.minute,
+            second: time.second,
+            offset: time.offset,
+        }),
+    }
+}
+
+fn leak(value: String) -> &'static str {
+    Box::leak(value.into_boxed_str())
+}
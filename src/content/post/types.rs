@@ -1,11 +1,172 @@
 use maud::Markup;
-use pulldown_cmark::HeadingLevel;
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd, TextMergeStream};
 
-#[derive(Clone, Copy)]
+/// Average adult silent reading speed for prose.
+const WORDS_PER_MINUTE: usize = 230;
+/// Code is read line by line rather than word by word, and much more slowly.
+const CODE_LINES_PER_MINUTE: usize = 30;
+
+/// Markdown extensions enabled for post bodies and every fragment rendered from them.
+pub const MARKDOWN_OPTIONS: Options = Options::ENABLE_TABLES
+    .union(Options::ENABLE_FOOTNOTES)
+    .union(Options::ENABLE_MATH);
+
+/// A calendar date, with the time of day when front matter gives an RFC 3339 timestamp.
+/// Dates without one are taken as midnight UTC.
+#[derive(Clone, Copy, Debug)]
 pub struct Date {
     pub year: u16,
     pub month: u8,
     pub day: u8,
+    pub time: Option<Time>,
+}
+
+/// Time of day, in the time zone the timestamp was written in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Time {
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    /// Minutes east of UTC.
+    pub offset: i16,
+}
+
+impl Time {
+    const MIDNIGHT_UTC: Self = Self {
+        hour: 0,
+        minute: 0,
+        second: 0,
+        offset: 0,
+    };
+
+    fn seconds_since_midnight(self) -> i64 {
+        i64::from(self.hour) * 3600 + i64::from(self.minute) * 60 + i64::from(self.second)
+    }
+
+    /// `+02:00` with `separator` `:`, `+0200` with none.
+    fn offset_label(self, separator: &str) -> String {
+        let sign = if self.offset < 0 { '-' } else { '+' };
+        let offset = self.offset.unsigned_abs();
+        format!("{}{:02}{}{:02}", sign, offset / 60, separator, offset % 60)
+    }
+}
+
+impl Date {
+    /// Days since 1970-01-01 in the proleptic Gregorian calendar.
+    pub fn days_since_epoch(self) -> i64 {
+        let month = i64::from(self.month);
+        let year = i64::from(self.year) - i64::from(month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + i64::from(self.day) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    /// Inverse of `days_since_epoch`.
+    pub fn from_days_since_epoch(days: i64) -> Self {
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days - era * 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        Self {
+            year: year as u16,
+            month: month as u8,
+            day: day as u8,
+            time: None,
+        }
+    }
+
+    /// Seconds since 1970-01-01T00:00:00Z.
+    pub fn timestamp(self) -> i64 {
+        let time = self.time.unwrap_or(Time::MIDNIGHT_UTC);
+        self.days_since_epoch() * 86_400 + time.seconds_since_midnight()
+            - i64::from(time.offset) * 60
+    }
+
+    /// Inverse of `timestamp`, in UTC.
+    pub fn from_timestamp(seconds: i64) -> Self {
+        let of_day = seconds.rem_euclid(86_400);
+        Self {
+            time: Some(Time {
+                hour: (of_day / 3600) as u8,
+                minute: (of_day / 60 % 60) as u8,
+                second: (of_day % 60) as u8,
+                offset: 0,
+            }),
+            ..Self::from_days_since_epoch(seconds.div_euclid(86_400))
+        }
+    }
+
+    /// The current moment in UTC.
+    pub fn now() -> Self {
+        let seconds = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        Self::from_timestamp(seconds as i64)
+    }
+
+    fn weekday_name(self) -> &'static str {
+        // 1970-01-01 was a Thursday.
+        const NAMES: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+        NAMES[self.days_since_epoch().rem_euclid(7) as usize]
+    }
+
+    fn month_name(self) -> &'static str {
+        const NAMES: [&str; 12] = [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ];
+        NAMES[usize::from(self.month.clamp(1, 12) - 1)]
+    }
+
+    /// E.g. `2026-04-12T09:30:00+02:00`, or `2026-04-12T00:00:00Z` for a date alone, as used
+    /// by Atom.
+    pub fn to_rfc3339(self) -> String {
+        let time = self.time.unwrap_or(Time::MIDNIGHT_UTC);
+        let zone = match time.offset {
+            0 => "Z".to_string(),
+            _ => time.offset_label(":"),
+        };
+        format!(
+            "{}T{:02}:{:02}:{:02}{}",
+            self, time.hour, time.minute, time.second, zone
+        )
+    }
+
+    /// E.g. `Sun, 12 Apr 2026 09:30:00 +0200`, or midnight UTC for a date alone, as used by RSS.
+    pub fn to_rfc2822(self) -> String {
+        let time = self.time.unwrap_or(Time::MIDNIGHT_UTC);
+        format!(
+            "{}, {:02} {} {:04} {:02}:{:02}:{:02} {}",
+            self.weekday_name(),
+            self.day,
+            self.month_name(),
+            self.year,
+            time.hour,
+            time.minute,
+            time.second,
+            time.offset_label("")
+        )
+    }
+
+    /// The date alone, or the full timestamp when there is a time, in the W3C format HTML
+    /// `<time datetime>` and sitemap `lastmod` take.
+    pub fn to_w3c_datetime(self) -> String {
+        match self.time {
+            Some(_) => self.to_rfc3339(),
+            None => self.to_string(),
+        }
+    }
 }
 
 impl std::fmt::Display for Date {
@@ -14,13 +175,10 @@ impl std::fmt::Display for Date {
     }
 }
 
+/// Dates are ordered by the moment they name, whatever time zone they were written in.
 impl Ord for Date {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.year.cmp(&other.year).then_with(|| {
-            self.month
-                .cmp(&other.month)
-                .then_with(|| self.day.cmp(&other.day))
-        })
+        self.timestamp().cmp(&other.timestamp())
     }
 }
 
@@ -32,7 +190,7 @@ impl PartialOrd for Date {
 
 impl PartialEq for Date {
     fn eq(&self, other: &Self) -> bool {
-        self.year == other.year && self.month == other.month && self.day == other.day
+        self.timestamp() == other.timestamp()
     }
 }
 
@@ -42,7 +200,34 @@ impl Eq for Date {}
 pub struct PostMeta {
     pub title: &'static str,
     pub published: Date,
+    /// When the post was last revised, from `updated` in front matter.
+    pub updated: Option<Date>,
     pub draft: bool,
+    /// Posts with a higher weight are listed first, whatever their date. 0 for most posts;
+    /// a positive weight pins a post to the top.
+    pub weight: i32,
+    pub tags: &'static [&'static str],
+    /// Show the table of contents; `toc: false` in front matter turns it off.
+    pub toc: bool,
+    /// Summary for link previews; the first paragraph is used when unset.
+    pub description: Option<&'static str>,
+    /// Preview image URL; the first image in the post is used when unset.
+    pub image: Option<&'static str>,
+}
+
+impl PostMeta {
+    /// `updated` when it is set and later than `published`, otherwise `published`.
+    pub fn last_modified(&self) -> Date {
+        self.updated
+            .map_or(self.published, |updated| updated.max(self.published))
+    }
+
+    /// The `updated` date worth showing next to `published`: one on a later day.
+    pub fn revised(&self) -> Option<Date> {
+        let day = |date: Date| (date.year, date.month, date.day);
+        self.updated
+            .filter(|updated| *updated > self.published && day(*updated) != day(self.published))
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -50,8 +235,96 @@ pub struct Post {
     pub id: &'static str,
     pub meta: PostMeta,
     pub markdown: &'static str,
-    pub events: fn() -> pulldown_cmark::TextMergeStream<'static, pulldown_cmark::Parser<'static>>,
 }
+
+impl Post {
+    pub fn events(&self) -> TextMergeStream<'static, Parser<'static>> {
+        TextMergeStream::new(Parser::new_ext(self.markdown, MARKDOWN_OPTIONS))
+    }
+
+    /// Newest first, with ties broken by id so posts published at the same moment keep their
+    /// order from one build to the next.
+    pub fn cmp_newest_first(&self, other: &Self) -> std::cmp::Ordering {
+        other
+            .meta
+            .published
+            .cmp(&self.meta.published)
+            .then_with(|| self.id.cmp(other.id))
+    }
+
+    /// The order posts are listed in: by `weight`, heaviest first, then newest first.
+    pub fn cmp_listing(&self, other: &Self) -> std::cmp::Ordering {
+        other
+            .meta
+            .weight
+            .cmp(&self.meta.weight)
+            .then_with(|| self.cmp_newest_first(other))
+    }
+
+    /// Words of prose plus an estimate of how long the post takes to read. Code blocks are
+    /// left out of the word count and timed by line instead, so a benchmark write-up full of
+    /// listings doesn't read as a short note.
+    pub fn reading_time(&self) -> ReadingTime {
+        let mut words = 0;
+        let mut code_lines = 0;
+        let mut in_code_block = false;
+        for event in self.events() {
+            match event {
+                Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+                Event::End(TagEnd::CodeBlock) => in_code_block = false,
+                Event::Text(text) if in_code_block => {
+                    code_lines += text.lines().filter(|line| !line.trim().is_empty()).count()
+                }
+                Event::Text(text) | Event::Code(text) => words += count_words(&text),
+                _ => {}
+            }
+        }
+
+        let minutes = (words * CODE_LINES_PER_MINUTE + code_lines * WORDS_PER_MINUTE)
+            .div_ceil(WORDS_PER_MINUTE * CODE_LINES_PER_MINUTE);
+        ReadingTime {
+            words,
+            minutes: minutes.max(1),
+        }
+    }
+}
+
+/// Punctuation left over between inline elements, like the `.` after a link, isn't a word.
+fn count_words(text: &str) -> usize {
+    text.split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .count()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReadingTime {
+    /// Words outside code blocks.
+    pub words: usize,
+    /// Rounded up, and never less than one.
+    pub minutes: usize,
+}
+
+impl ReadingTime {
+    /// `1,234 words`, with thousands separated.
+    pub fn words_label(self) -> String {
+        let digits = self.words.to_string();
+        let mut label = String::new();
+        for (idx, digit) in digits.chars().enumerate() {
+            if idx > 0 && (digits.len() - idx).is_multiple_of(3) {
+                label.push(',');
+            }
+            label.push(digit);
+        }
+        let unit = if self.words == 1 { "word" } else { "words" };
+        format!("{} {}", label, unit)
+    }
+
+    /// `6 min read`.
+    pub fn minutes_label(self) -> String {
+        format!("{} min read", self.minutes)
+    }
+}
+
 #[derive(Debug)]
 pub struct Frame {
     pub kind: FrameKind,
@@ -88,6 +361,7 @@ pub enum FrameKind {
     TableRow,
     TableCell,
     TableHeadCell,
+    FootnoteDefinition(String),
 }
 
 #[derive(Debug)]
@@ -97,3 +371,140 @@ pub enum RenderNode {
     BlockQuote { buffer: Vec<RenderNode> },
     CodeBlock { info: Option<String>, text: String },
 }
+
+#[cfg(test)]
+impl PostMeta {
+    /// A published post with nothing optional set. Test fixtures start from it and override
+    /// the fields they care about with `..PostMeta::TEST`.
+    pub const TEST: Self = Self {
+        title: "Post",
+        published: Date {
+            year: 2025,
+            month: 1,
+            day: 1,
+            time: None,
+        },
+        updated: None,
+        draft: false,
+        weight: 0,
+        tags: &[],
+        toc: false,
+        description: None,
+        image: None,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn post(markdown: &'static str) -> Post {
+        Post {
+            id: "post",
+            meta: PostMeta::TEST,
+            markdown,
+        }
+    }
+
+    #[test]
+    fn converts_days_since_epoch_both_ways() {
+        for (days, date) in [
+            (0, "1970-01-01"),
+            (11_016, "2000-02-29"),
+            (20_555, "2026-04-12"),
+            (-1, "1969-12-31"),
+        ] {
+            let converted = Date::from_days_since_epoch(days);
+            assert_eq!(converted.to_string(), date);
+            assert_eq!(converted.days_since_epoch(), days);
+        }
+    }
+
+    #[test]
+    fn orders_timestamps_by_the_moment_they_name() {
+        let date = |day, time| Date {
+            year: 2026,
+            month: 4,
+            day,
+            time,
+        };
+        let at = |hour, offset| {
+            Some(Time {
+                hour,
+                minute: 30,
+                second: 0,
+                offset,
+            })
+        };
+        let berlin = date(12, at(9, 120));
+        assert_eq!(berlin, date(12, at(7, 0)));
+        assert!(date(12, None) < berlin);
+        // Half past one in Berlin is still the day before in UTC.
+        assert!(date(12, at(1, 120)) < date(12, None));
+        assert_eq!(berlin.to_string(), "2026-04-12");
+        assert_eq!(berlin.to_w3c_datetime(), "2026-04-12T09:30:00+02:00");
+        assert_eq!(date(12, None).to_w3c_datetime(), "2026-04-12");
+        assert_eq!(
+            Date::from_timestamp(berlin.timestamp()).to_rfc3339(),
+            "2026-04-12T07:30:00Z"
+        );
+    }
+
+    #[test]
+    fn shows_updates_from_a_later_day_only() {
+        let date = |day, hour| Date {
+            year: 2026,
+            month: 4,
+            day,
+            time: Some(Time {
+                hour,
+                minute: 0,
+                second: 0,
+                offset: 0,
+            }),
+        };
+        let meta = |updated| PostMeta {
+            published: date(12, 9),
+            updated,
+            ..post("").meta
+        };
+        assert_eq!(meta(None).revised(), None);
+        assert_eq!(meta(Some(date(12, 18))).revised(), None);
+        assert_eq!(meta(Some(date(11, 18))).revised(), None);
+        assert_eq!(meta(Some(date(11, 18))).last_modified(), date(12, 9));
+        assert_eq!(meta(Some(date(20, 8))).revised(), Some(date(20, 8)));
+        assert_eq!(meta(Some(date(20, 8))).last_modified(), date(20, 8));
+    }
+
+    #[test]
+    fn counts_prose_and_times_code_separately() {
+        let short = post("# Hello\n\nA *short* note with `inline code`.");
+        assert_eq!(
+            short.reading_time(),
+            ReadingTime {
+                words: 7,
+                minutes: 1
+            }
+        );
+
+        let prose = "word ".repeat(460);
+        let code = "let x = 1;\n\n".repeat(60);
+        let markdown = format!("{}\n\n```rust\n{}```\n", prose, code).leak();
+        assert_eq!(
+            post(markdown).reading_time(),
+            ReadingTime {
+                words: 460,
+                minutes: 4
+            }
+        );
+    }
+
+    #[test]
+    fn separates_thousands_in_word_labels() {
+        let label = |words| ReadingTime { words, minutes: 1 }.words_label();
+        assert_eq!(label(1), "1 word");
+        assert_eq!(label(999), "999 words");
+        assert_eq!(label(1234), "1,234 words");
+        assert_eq!(label(1234567), "1,234,567 words");
+    }
+}
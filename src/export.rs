@@ -0,0 +1,273 @@
+use axum::extract::{Path as UrlPath, State};
+use maud::Markup;
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{pages, state::AppState};
+
+/// Asset directories produced by `build.rs` that pages link to.
+const ASSET_DIRS: [&str; 3] = ["style", "img", "js"];
+
+pub struct ExportSummary {
+    pub pages: usize,
+    pub assets: usize,
+}
+
+/// Writes every page the server would render, the feeds and the built assets to `out_dir`, so
+/// the site can be hosted from a plain static file server. Pages go through the same handlers
+/// as live requests, then every internal link is checked against the files written.
+pub async fn export(
+    state: Arc<AppState>,
+    asset_root: &Path,
+    out_dir: &Path,
+) -> Result<ExportSummary, String> {
+    fs::create_dir_all(out_dir)
+        .map_err(|err| format!("failed to create {}: {}", out_dir.display(), err))?;
+    let out_dir = out_dir
+        .canonicalize()
+        .map_err(|err| format!("failed to resolve {}: {}", out_dir.display(), err))?;
+    if let Ok(asset_root) = asset_root.canonicalize()
+        && out_dir.starts_with(&asset_root)
+    {
+        return Err(format!(
+            "output directory {} is inside the asset root {}",
+            out_dir.display(),
+            asset_root.display()
+        ));
+    }
+
+    let mut files: Vec<(String, Vec<u8>)> = Vec::new();
+    let mut page = |path: &str, markup: Markup| {
+        files.push((page_file(path), markup.into_string().into_bytes()))
+    };
+
+    page("/", pages::index(State(state.clone())).await);
+    page("/posts", pages::all_posts(State(state.clone())).await);
+    page("/archive", pages::archive(State(state.clone())).await);
+    page("/about", pages::about(State(state.clone())).await);
+    page("/tags", pages::tags(State(state.clone())).await);
+    let post_state = state.post_state();
+    for tag in post_state.tags().keys() {
+        let (_, markup) = pages::tag(State(state.clone()), UrlPath(tag.to_string())).await;
+        page(&format!("/tags/{}", tag), markup);
+    }
+    let (_, not_found) = pages::not_found(State(state.clone())).await;
+    files.push(("404.html".to_string(), not_found.into_string().into_bytes()));
+
+    for post in post_state.posts() {
+        let html = post_state
+            .page(post.id)
+            .ok_or_else(|| format!("post {} has no rendered page", post.id))?;
+        files.push((
+            page_file(&format!("/post/{}", post.id)),
+            html.body().to_vec(),
+        ));
+        if let Some(card) = post_state.card(post.id) {
+            files.push((format!("og/{}.png", post.id), card.body().to_vec()));
+        }
+    }
+    let pages = files
+        .iter()
+        .filter(|(file, _)| file.ends_with(".html"))
+        .count();
+    let documents = [
+        ("feed.xml", post_state.atom_feed()),
+        ("rss.xml", post_state.rss_feed()),
+        ("sitemap.xml", post_state.sitemap()),
+        ("robots.txt", post_state.robots()),
+    ];
+    for (file, document) in documents {
+        files.push((file.to_string(), document.body().to_vec()));
+    }
+
+    for (file, contents) in &files {
+        write_file(&out_dir.join(file), contents)?;
+    }
+    let mut assets = 0;
+    for dir in ASSET_DIRS {
+        assets += copy_dir(&asset_root.join(dir), &out_dir.join(dir))
+            .map_err(|err| format!("failed to copy {} assets: {}", dir, err))?;
+    }
+
+    check_links(&out_dir)?;
+    Ok(ExportSummary { pages, assets })
+}
+
+/// `/posts` is written as `posts/index.html`, which static hosts serve for both `/posts` and
+/// `/posts/`.
+fn page_file(path: &str) -> String {
+    let path = path.trim_matches('/');
+    if path.is_empty() {
+        "index.html".to_string()
+    } else {
+        format!("{}/index.html", path)
+    }
+}
+
+fn write_file(path: &Path, contents: impl AsRef<[u8]>) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|err| format!("failed to create {}: {}", parent.display(), err))?;
+    }
+    fs::write(path, contents).map_err(|err| format!("failed to write {}: {}", path.display(), err))
+}
+
+fn copy_dir(src: &Path, dst: &Path) -> io::Result<usize> {
+    if !src.is_dir() {
+        return Ok(0);
+    }
+    fs::create_dir_all(dst)?;
+    let mut copied = 0;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let target = dst.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copied += copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+            copied += 1;
+        }
+    }
+    Ok(copied)
+}
+
+/// Checks that every root-relative `href`/`src` in the exported HTML points at a written file,
+/// and that fragments name an element id on the target page.
+fn check_links(out_dir: &Path) -> Result<(), String> {
+    let mut pages = BTreeMap::new();
+    collect_pages(out_dir, out_dir, &mut pages)
+        .map_err(|err| format!("failed to read back {}: {}", out_dir.display(), err))?;
+    let ids = pages
+        .iter()
+        .map(|(file, html)| (file.clone(), element_ids(html)))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut broken = Vec::new();
+    for (file, html) in &pages {
+        for link in links(html) {
+            let (path, fragment) = match link.split_once('#') {
+                Some((path, fragment)) => (path, Some(fragment)),
+                None => (link, None),
+            };
+            let path = path.split('?').next().unwrap_or_default();
+            let target = if path.is_empty() {
+                Some(file.clone())
+            } else if path.starts_with('/') && !path.starts_with("//") {
+                resolve(out_dir, path)
+            } else {
+                // External, `mailto:`, `data:` and page-relative links aren't ours to check.
+                continue;
+            };
+
+            let found = match (&target, fragment) {
+                (None, _) => false,
+                (Some(_), None | Some("")) => true,
+                (Some(target), Some(fragment)) => {
+                    ids.get(target).is_none_or(|ids| ids.contains(fragment))
+                }
+            };
+            if !found {
+                broken.push(format!("{} → {}", file.display(), link));
+            }
+        }
+    }
+
+    if broken.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("broken links:\n  {}", broken.join("\n  ")))
+    }
+}
+
+fn collect_pages(root: &Path, dir: &Path, pages: &mut BTreeMap<PathBuf, String>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_pages(root, &path, pages)?;
+        } else if path.extension().is_some_and(|ext| ext == "html") {
+            let relative = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+            pages.insert(relative, fs::read_to_string(&path)?);
+        }
+    }
+    Ok(())
+}
+
+/// The exported file a root-relative URL path is served from, if any.
+fn resolve(out_dir: &Path, path: &str) -> Option<PathBuf> {
+    let relative = path.trim_start_matches('/');
+    let candidate = Path::new(relative);
+    if !relative.is_empty() && !relative.ends_with('/') && out_dir.join(candidate).is_file() {
+        return Some(candidate.to_path_buf());
+    }
+    let index = candidate.join("index.html");
+    out_dir.join(&index).is_file().then_some(index)
+}
+
+fn links(html: &str) -> impl Iterator<Item = &str> {
+    attribute_values(html, " href=\"").chain(attribute_values(html, " src=\""))
+}
+
+fn element_ids(html: &str) -> HashSet<&str> {
+    attribute_values(html, " id=\"").collect()
+}
+
+/// Values of one attribute across a document. Maud escapes quotes inside attribute values, so
+/// the value always ends at the next `"`.
+fn attribute_values<'a>(html: &'a str, prefix: &'a str) -> impl Iterator<Item = &'a str> {
+    html.match_indices(prefix).filter_map(move |(start, _)| {
+        let value = &html[start + prefix.len()..];
+        value.find('"').map(|end| &value[..end])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("blog-export-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn maps_url_paths_to_index_files() {
+        assert_eq!(page_file("/"), "index.html");
+        assert_eq!(page_file("/post/bloom"), "post/bloom/index.html");
+    }
+
+    #[test]
+    fn reports_missing_pages_and_fragments() {
+        let dir = scratch_dir("links");
+        write_file(
+            &dir.join("index.html"),
+            r##"<a href="/post/bloom#hashing"></a><a href="/post/bloom/"></a><img src="/img/a.png"><a href="https://example.com/missing"></a>"##,
+        )
+        .unwrap();
+        write_file(
+            &dir.join("post/bloom/index.html"),
+            r##"<h2 id="hashing"></h2><a href="#hashing"></a>"##,
+        )
+        .unwrap();
+        write_file(&dir.join("img/a.png"), "").unwrap();
+
+        assert!(check_links(&dir).is_ok());
+
+        write_file(
+            &dir.join("about/index.html"),
+            r##"<a href="/posts"></a><a href="/post/bloom#setup"></a><a href="#top"></a>"##,
+        )
+        .unwrap();
+        let err = check_links(&dir).unwrap_err();
+
+        assert!(err.contains("about/index.html → /posts"));
+        assert!(err.contains("about/index.html → /post/bloom#setup"));
+        assert!(err.contains("about/index.html → #top"));
+        assert!(!err.contains("index.html → /img/a.png"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,5 +1,7 @@
 pub mod common;
 pub(crate) mod component;
+pub mod config;
 pub mod content;
+pub mod export;
 pub mod pages;
 pub mod state;
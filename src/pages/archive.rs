@@ -0,0 +1,89 @@
+use crate::{common::layout, content::Post, state::Site};
+use maud::{Markup, html};
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// Posts grouped by the month they were published in, newest first.
+struct Month<'a> {
+    year: u16,
+    month: u8,
+    posts: &'a [&'static Post],
+}
+
+/// Splits the newest-first post list into runs sharing a publication month.
+fn months<'a>(posts: &'a [&'static Post]) -> Vec<Month<'a>> {
+    posts
+        .chunk_by(|a, b| {
+            (a.meta.published.year, a.meta.published.month)
+                == (b.meta.published.year, b.meta.published.month)
+        })
+        .map(|posts| Month {
+            year: posts[0].meta.published.year,
+            month: posts[0].meta.published.month,
+            posts,
+        })
+        .collect()
+}
+
+fn count(posts: usize) -> String {
+    if posts == 1 {
+        "1 post".to_string()
+    } else {
+        format!("{} posts", posts)
+    }
+}
+
+pub async fn page(site: &Site, posts: &[&'static Post]) -> Markup {
+    // The listings put pinned posts first; the archive is strictly by date.
+    let mut posts = posts.to_vec();
+    posts.sort_by(|a, b| a.cmp_newest_first(b));
+    let months = months(&posts);
+    let years = months.chunk_by(|a, b| a.year == b.year);
+    let content = html! {
+        div class="mt-4 space-y-4" {
+            h2 class="text-xl font-semibold" { "Archive" }
+            @for months in years {
+                @let total = months.iter().map(|month| month.posts.len()).sum::<usize>();
+                section class="mt-8" {
+                    h3 class="text-lg font-semibold text-white" {
+                        (months[0].year)
+                        span class="font-normal text-gray-500" { " · " (count(total)) }
+                    }
+                    @for month in months {
+                        h4 class="mt-4 text-white/80" {
+                            (MONTHS[usize::from(month.month.clamp(1, 12) - 1)])
+                            span class="text-gray-500" { " · " (count(month.posts.len())) }
+                        }
+                        ul class="mt-2" {
+                            @for post in month.posts {
+                                li class="list-none" {
+                                    span class="text-gray-500 tabular-nums" {
+                                        (format!("{:02}", post.meta.published.day)) " · "
+                                    }
+                                    a class="text-white/80 hover:text-white" href=(format!("/post/{}", post.id)) {
+                                        (post.meta.title)
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    layout(site, "Archive", content)
+}
@@ -1,20 +1,30 @@
-use crate::{common::layout, content::Post};
+use crate::{
+    common::{asset_url, layout},
+    content::{Post, PostState},
+    state::{Site, markdown},
+};
 use maud::{Markup, html};
 
-pub fn post_section(posts: &[&'static Post]) -> Markup {
+/// Title, date and reading time of each post, with its excerpt underneath when it has one.
+pub fn post_section(posts: &[&'static Post], post_state: &PostState) -> Markup {
     let content = html! {
         @for post in posts {
-            div {
+            div class="mb-3" {
                 a class="text-white/80 hover:text-white" href=(format!("/post/{}", post.id)) {
                     (post.meta.title)
                 }
                 span class="text-gray-500 whitespace-nowrap" {
                     " · "
-                    (post.meta.published.year)
-                    "-"
-                    (post.meta.published.month)
-                    "-"
-                    (post.meta.published.day)
+                    (post.meta.published)
+                    @if let Some(updated) = post.meta.revised() {
+                        " (updated " (updated) ")"
+                    }
+                    @if let Some(reading_time) = post_state.reading_time(post.id) {
+                        " · " (reading_time.minutes_label())
+                    }
+                }
+                @if let Some(excerpt) = post_state.excerpt(post.id) {
+                    div class="mt-1 text-sm [&_p]:text-gray-400 [&_p]:mt-1" { (excerpt) }
                 }
             }
         }
@@ -23,28 +33,28 @@ pub fn post_section(posts: &[&'static Post]) -> Markup {
     content
 }
 
-pub async fn page(posts: &[&'static Post]) -> Markup {
+pub async fn page(site: &Site, post_state: &PostState) -> Markup {
+    let posts = post_state.posts();
+    let latest = &posts[..posts.len().min(site.home.latest)];
     let content = html! {
         div class="flex flex-col items-start gap-6 sm:flex-row sm:items-center my-4 mt-10" {
-            img src="/img/avatar.png"
-                alt="Portrait of Lucas de Castro"
+            img src=(asset_url(&site.avatar))
+                alt={ "Portrait of " (site.author) }
                 class="size-32 shrink-0 rounded-full border-4 border-white/20 object-cover";
-            p class="m-0 text-base leading-relaxed" {
-                "Hey, I'm Lucas de Castro. I'm a backend software engineer at "
-                a href="https://amo.co/" { "amo" }
-                ", and this "
-                a href="https://github.com/ae2rs/blog.rs" { "open source" }
-                " blog is where I (irregularly) post about pretty much anything I find interesting."
-                br;
+            div class="m-0 text-base leading-relaxed [&_p]:m-0" {
+                (markdown(&site.home.intro))
                 a class="mt-2 inline-block" href="/about" { "Read more ››" }
             }
         }
 
         section class="mt-20" {
             h2 class="text-xl font-semibold" { "Latest posts" }
-            ul class="mt-4" { (post_section(posts)) }
+            ul class="mt-4" { (post_section(latest, post_state)) }
+            @if latest.len() < posts.len() {
+                a class="inline-block" href="/posts" { "All posts ››" }
+            }
         }
     };
 
-    layout("Lucas' Hut", content)
+    layout(site, &site.title, content)
 }
@@ -1,21 +1,98 @@
 mod about;
+mod archive;
 mod index;
 mod not_found;
 mod posts;
+mod search;
+mod tags;
 
-use axum::extract::State;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
 use maud::Markup;
+use serde::Deserialize;
 use std::sync::Arc;
 
 use crate::state::AppState;
 
-pub use about::page as about;
-pub use not_found::page as not_found;
-
 pub async fn index(State(state): State<Arc<AppState>>) -> Markup {
-    index::page(state.posts()).await
+    index::page(state.site(), &state.post_state()).await
+}
+
+pub async fn about(State(state): State<Arc<AppState>>) -> Markup {
+    about::page(state.site()).await
+}
+
+#[derive(Default, Deserialize)]
+pub struct PageParams {
+    page: Option<usize>,
+}
+
+pub async fn posts(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<PageParams>,
+) -> (StatusCode, Markup) {
+    let site = state.site();
+    let post_state = state.post_state();
+    let posts = post_state.posts();
+    let pages = posts.len().div_ceil(site.posts.per_page).max(1);
+    let page = params.page.unwrap_or(1);
+    if !(1..=pages).contains(&page) {
+        return not_found::page(site).await;
+    }
+
+    let start = (page - 1) * site.posts.per_page;
+    let end = posts.len().min(start + site.posts.per_page);
+    let pager = posts::Pager { page, pages };
+    let markup = posts::page(site, &posts[start..end], &post_state, Some(pager)).await;
+    (StatusCode::OK, markup)
+}
+
+/// Every post on a single `/posts` page, for the static export, where a query string can't
+/// select a page.
+pub async fn all_posts(State(state): State<Arc<AppState>>) -> Markup {
+    let post_state = state.post_state();
+    posts::page(state.site(), post_state.posts(), &post_state, None).await
+}
+
+pub async fn archive(State(state): State<Arc<AppState>>) -> Markup {
+    archive::page(state.site(), state.post_state().posts()).await
+}
+
+pub async fn tags(State(state): State<Arc<AppState>>) -> Markup {
+    tags::index(state.site(), state.post_state().tags()).await
+}
+
+pub async fn tag(
+    State(state): State<Arc<AppState>>,
+    Path(tag): Path<String>,
+) -> (StatusCode, Markup) {
+    let tag = tag.to_lowercase();
+    let post_state = state.post_state();
+    match post_state.tagged(tag.as_str()) {
+        Some(posts) => (
+            StatusCode::OK,
+            tags::page(state.site(), &tag, posts, &post_state).await,
+        ),
+        None => not_found::page(state.site()).await,
+    }
+}
+
+#[derive(Deserialize)]
+pub struct SearchParams {
+    #[serde(default)]
+    q: String,
+}
+
+pub async fn search(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<SearchParams>,
+) -> Markup {
+    let query = params.q.trim();
+    search::page(state.site(), query, &state.post_state().search(query)).await
 }
 
-pub async fn posts(State(state): State<Arc<AppState>>) -> Markup {
-    posts::page(state.posts()).await
+pub async fn not_found(State(state): State<Arc<AppState>>) -> (StatusCode, Markup) {
+    not_found::page(state.site()).await
 }
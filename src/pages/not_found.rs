@@ -1,14 +1,14 @@
 use axum::http::StatusCode;
 use maud::{Markup, html};
 
-use crate::common::layout;
+use crate::{common::layout, state::Site};
 
-pub async fn page() -> (StatusCode, Markup) {
+pub async fn page(site: &Site) -> (StatusCode, Markup) {
     let content = html! {
         div class="min-h-[60vh] flex flex-col items-center justify-center text-center" {
             h2 class="text-md font-medium text-white/60" { "not found — sorry." }
         }
     };
 
-    (StatusCode::NOT_FOUND, layout("Not Found", content))
+    (StatusCode::NOT_FOUND, layout(site, "Not Found", content))
 }
@@ -1,31 +1,56 @@
-use crate::{common::layout, content::Post};
+use crate::{
+    common::layout,
+    content::{Post, PostState},
+    state::Site,
+};
 use maud::{Markup, html};
 
-pub async fn page(posts: &[&'static Post]) -> Markup {
-    let posts = html! {
-        @for post in posts {
-            div {
-                a class="text-white/80 hover:text-white" href=(format!("/post/{}", post.id)) {
-                    (post.meta.title)
-                }
-                span class="text-gray-500 whitespace-nowrap" {
-                    " · "
-                    (post.meta.published.year)
-                    "-"
-                    (post.meta.published.month)
-                    "-"
-                    (post.meta.published.day)
-                }
-            }
+use super::index::post_section;
+
+/// Where a page of `/posts` sits among the others. Pages are numbered from 1.
+pub struct Pager {
+    pub page: usize,
+    pub pages: usize,
+}
+
+impl Pager {
+    fn href(page: usize) -> String {
+        if page == 1 {
+            "/posts".to_string()
+        } else {
+            format!("/posts?page={}", page)
         }
-    };
+    }
+}
 
+pub async fn page(
+    site: &Site,
+    posts: &[&'static Post],
+    post_state: &PostState,
+    pager: Option<Pager>,
+) -> Markup {
     let content = html! {
         div class="mt-4 space-y-4" {
             h2 class="text-xl font-semibold" { "Posts" }
-            ul class="mt-4" { (posts) }
+            ul class="mt-4" { (post_section(posts, post_state)) }
+            nav class="flex items-center gap-4 text-gray-500" aria-label="Pagination" {
+                @if let Some(pager) = &pager {
+                    @if pager.page > 1 {
+                        a href=(Pager::href(pager.page - 1)) rel="prev" { "‹‹ Newer" }
+                    }
+                    span { "Page " (pager.page) " of " (pager.pages) }
+                    @if pager.page < pager.pages {
+                        a href=(Pager::href(pager.page + 1)) rel="next" { "Older ››" }
+                    }
+                }
+                a class="ml-auto" href="/archive" { "Archive" }
+            }
         }
     };
 
-    layout("Posts", content)
+    let title = match pager {
+        Some(Pager { page, .. }) if page > 1 => format!("Posts, page {}", page),
+        _ => "Posts".to_string(),
+    };
+    layout(site, &title, content)
 }
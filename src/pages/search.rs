@@ -0,0 +1,41 @@
+use crate::{common::layout, content::SearchHit, state::Site};
+use maud::{Markup, html};
+
+pub async fn page(site: &Site, query: &str, hits: &[SearchHit]) -> Markup {
+    let content = html! {
+        div class="mt-4 space-y-4" {
+            h2 class="text-xl font-semibold" { "Search" }
+            form action="/search" method="get" role="search" {
+                input
+                    class="w-full rounded-md border border-white/15 bg-white/5 px-3 py-2 text-white placeholder:text-gray-500 focus:border-white/40 focus:outline-none"
+                    type="search"
+                    name="q"
+                    value=(query)
+                    placeholder="Search posts…"
+                    aria-label="Search posts"
+                    autofocus;
+            }
+            @if !query.is_empty() {
+                @if hits.is_empty() {
+                    p class="text-gray-500" { "No posts match “" (query) "”." }
+                } @else {
+                    ul class="mt-6 space-y-6" {
+                        @for hit in hits {
+                            li class="list-none" {
+                                a class="text-white/80 hover:text-white" href=(format!("/post/{}", hit.post.id)) {
+                                    (hit.post.meta.title)
+                                }
+                                span class="text-gray-500 whitespace-nowrap" {
+                                    " · " (hit.post.meta.published)
+                                }
+                                p class="mt-1 text-sm text-gray-400" { (hit.snippet) }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    layout(site, "Search", content)
+}
@@ -0,0 +1,46 @@
+use crate::{
+    common::layout,
+    content::{Post, PostState},
+    state::Site,
+};
+use maud::{Markup, html};
+use std::collections::BTreeMap;
+
+use super::index::post_section;
+
+pub async fn index(site: &Site, tags: &BTreeMap<&'static str, Vec<&'static Post>>) -> Markup {
+    let content = html! {
+        div class="mt-4 space-y-4" {
+            h2 class="text-xl font-semibold" { "Tags" }
+            ul class="mt-4" {
+                @for (tag, posts) in tags {
+                    li class="list-none" {
+                        a class="text-white/80 hover:text-white" href=(format!("/tags/{}", tag)) {
+                            "#" (tag)
+                        }
+                        span class="text-gray-500 whitespace-nowrap" { " · " (posts.len()) }
+                    }
+                }
+            }
+        }
+    };
+
+    layout(site, "Tags", content)
+}
+
+pub async fn page(
+    site: &Site,
+    tag: &str,
+    posts: &[&'static Post],
+    post_state: &PostState,
+) -> Markup {
+    let content = html! {
+        div class="mt-4 space-y-4" {
+            h2 class="text-xl font-semibold" { "Posts tagged #" (tag) }
+            ul class="mt-4" { (post_section(posts, post_state)) }
+            a class="inline-block" href="/tags" { "‹‹ All tags" }
+        }
+    };
+
+    layout(site, &format!("#{}", tag), content)
+}
@@ -1,27 +1,131 @@
-use crate::content::{Post, PostState, format::highlight::Highlighter};
+use std::{
+    path::Path,
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use super::Site;
+use crate::content::{Avatar, Date, PostState, format::highlight::Highlighter};
 
 pub struct AppState {
-    post_state: PostState,
+    site: Site,
+    highlighter: Highlighter,
+    /// Drawn on every preview card, so it is loaded once.
+    avatar: Avatar,
+    /// Key for `/preview` tokens; previews are off without one.
+    preview_secret: Option<String>,
+    post_state: RwLock<Arc<PostState>>,
+    /// Set while scheduled posts are being published, so only one rebuild runs at a time.
+    publishing: AtomicBool,
+    #[cfg(feature = "hot-reload")]
+    reload: tokio::sync::broadcast::Sender<()>,
 }
 
 impl AppState {
-    pub fn new() -> Self {
+    /// `asset_root` is where the site's avatar is read from for preview cards.
+    pub fn new(asset_root: &Path) -> Self {
+        Self::with_site(Site::load(), asset_root)
+    }
+
+    /// State for `blog export`. `/search` needs the server to answer queries, so it is left
+    /// out of the navigation before any page is rendered.
+    pub fn for_export(asset_root: &Path) -> Self {
+        let mut site = Site::load();
+        site.nav.retain(|link| link.href != "/search");
+        Self::with_site(site, asset_root)
+    }
+
+    fn with_site(site: Site, asset_root: &Path) -> Self {
         let highlighter = Highlighter::new();
-        let post_state = PostState::new(&highlighter);
-        Self { post_state }
+        let avatar = Avatar::load(asset_root, &site.avatar);
+        let post_state = PostState::new(&site, &highlighter, &avatar);
+        Self {
+            site,
+            highlighter,
+            avatar,
+            preview_secret: None,
+            post_state: RwLock::new(Arc::new(post_state)),
+            publishing: AtomicBool::new(false),
+            #[cfg(feature = "hot-reload")]
+            reload: tokio::sync::broadcast::channel(16).0,
+        }
     }
 
-    pub fn posts(&self) -> &[&'static Post] {
-        self.post_state.posts()
+    /// Enables draft previews, with tokens derived from `secret`.
+    pub fn with_preview_secret(mut self, secret: Option<String>) -> Self {
+        self.preview_secret = secret;
+        self
     }
 
-    pub fn post_page(&self, id: &str) -> Option<&String> {
-        self.post_state.page(id)
+    pub fn site(&self) -> &Site {
+        &self.site
+    }
+
+    pub fn preview_secret(&self) -> Option<&str> {
+        self.preview_secret.as_deref()
+    }
+
+    /// Snapshot of the rendered posts. Hot reload replaces the whole state, so a request
+    /// keeps seeing a consistent set of pages even if a reload lands halfway through it.
+    ///
+    /// The first request on or after a scheduled post's date starts re-rendering the posts in
+    /// the background so it shows up everywhere at once, without a redeploy. Requests keep
+    /// getting the previous state until the new one is swapped in.
+    pub fn post_state(self: &Arc<Self>) -> Arc<PostState> {
+        let now = Date::now();
+        let post_state = self
+            .post_state
+            .read()
+            .expect("post state lock poisoned")
+            .clone();
+        if post_state.is_due(now) && !self.publishing.swap(true, Ordering::AcqRel) {
+            let state = self.clone();
+            tokio::task::spawn_blocking(move || state.publish_scheduled(now));
+        }
+        post_state
+    }
+
+    fn publish_scheduled(&self, now: Date) {
+        let post_state = self
+            .post_state
+            .read()
+            .expect("post state lock poisoned")
+            .clone();
+        let rebuilt =
+            Arc::new(post_state.rebuild(&self.site, &self.highlighter, &self.avatar, now));
+        let mut post_state = self.post_state.write().expect("post state lock poisoned");
+        // A hot reload may have swapped in an up to date state in the meantime.
+        if post_state.is_due(now) {
+            *post_state = rebuilt;
+            log::info!("published posts scheduled up to {}", now.to_rfc3339());
+        }
+        drop(post_state);
+        self.publishing.store(false, Ordering::Release);
+    }
+
+    /// Re-reads `content/post` from disk, swaps in the re-rendered posts and notifies open
+    /// browser tabs. The previous state is kept if a post fails to load.
+    #[cfg(feature = "hot-reload")]
+    pub fn reload_posts(&self) -> Result<(), String> {
+        let posts = crate::content::load_posts()?;
+        let post_state = PostState::from_posts(
+            posts,
+            &self.site,
+            &self.highlighter,
+            &self.avatar,
+            Date::now(),
+        );
+        let post_state = Arc::new(post_state);
+        *self.post_state.write().expect("post state lock poisoned") = post_state;
+        // Nobody listening just means no tab is open.
+        let _ = self.reload.send(());
+        Ok(())
     }
-}
 
-impl Default for AppState {
-    fn default() -> Self {
-        Self::new()
+    #[cfg(feature = "hot-reload")]
+    pub fn subscribe_reload(&self) -> tokio::sync::broadcast::Receiver<()> {
+        self.reload.subscribe()
     }
 }
@@ -1,3 +1,9 @@
 mod app;
+#[cfg(feature = "hot-reload")]
+mod reload;
+mod site;
 
 pub use app::AppState;
+#[cfg(feature = "hot-reload")]
+pub use reload::{live_reload, watch_content};
+pub use site::{Link, Site, markdown};
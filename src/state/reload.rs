@@ -0,0 +1,51 @@
+use axum::{
+    extract::State,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use notify::{RecursiveMode, Watcher};
+use std::{convert::Infallible, path::Path, sync::Arc, time::Duration};
+use tokio_stream::{Stream, StreamExt, wrappers::BroadcastStream};
+
+use super::AppState;
+
+/// Editors save in several steps (temp file, rename, chmod); wait for the burst to settle.
+const DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Watches `content/` and reloads posts whenever anything under it changes.
+pub fn watch_content(state: Arc<AppState>) {
+    let content_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("content");
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if event.is_ok_and(|event| !event.kind.is_access()) {
+            let _ = tx.send(());
+        }
+    })
+    .expect("failed to create content watcher");
+    watcher
+        .watch(&content_dir, RecursiveMode::Recursive)
+        .unwrap_or_else(|err| panic!("failed to watch {}: {}", content_dir.display(), err));
+
+    tokio::spawn(async move {
+        let _watcher = watcher;
+        while rx.recv().await.is_some() {
+            tokio::time::sleep(DEBOUNCE).await;
+            while rx.try_recv().is_ok() {}
+
+            let state = state.clone();
+            match tokio::task::spawn_blocking(move || state.reload_posts()).await {
+                Ok(Ok(())) => log::info!("reloaded posts from {}", content_dir.display()),
+                Ok(Err(err)) => log::error!("failed to reload posts: {}", err),
+                Err(err) => log::error!("post reload task failed: {}", err),
+            }
+        }
+    });
+}
+
+/// Server-sent events telling `live-reload.js` to refresh the page after a reload.
+pub async fn live_reload(
+    State(state): State<Arc<AppState>>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let events =
+        BroadcastStream::new(state.subscribe_reload()).map(|_| Ok(Event::default().data("reload")));
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
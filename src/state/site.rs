@@ -0,0 +1,180 @@
+use maud::{Markup, PreEscaped};
+use pulldown_cmark::{Parser, html};
+use serde::Deserialize;
+
+/// Site identity from `site.toml`, embedded at build time.
+#[derive(Debug, Deserialize)]
+pub struct Site {
+    pub title: String,
+    pub author: String,
+    #[serde(default)]
+    pub description: String,
+    /// Absolute URL without a trailing slash.
+    pub base_url: String,
+    #[serde(default = "default_avatar")]
+    pub avatar: String,
+    #[serde(default)]
+    pub nav: Vec<Link>,
+    #[serde(default)]
+    pub social: Vec<Link>,
+    #[serde(default)]
+    pub home: Home,
+    #[serde(default)]
+    pub about: About,
+    #[serde(default)]
+    pub posts: Listing,
+    #[serde(default)]
+    pub robots: Robots,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Link {
+    pub label: String,
+    pub href: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Home {
+    /// Markdown.
+    #[serde(default)]
+    pub intro: String,
+    /// How many of the most recent posts the homepage lists.
+    #[serde(default = "default_latest")]
+    pub latest: usize,
+}
+
+impl Default for Home {
+    fn default() -> Self {
+        Self {
+            intro: String::new(),
+            latest: default_latest(),
+        }
+    }
+}
+
+/// The `/posts` listing.
+#[derive(Debug, Deserialize)]
+pub struct Listing {
+    /// Posts per page; later pages are reached with `?page=N`.
+    #[serde(default = "default_per_page")]
+    pub per_page: usize,
+}
+
+impl Default for Listing {
+    fn default() -> Self {
+        Self {
+            per_page: default_per_page(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct About {
+    #[serde(default)]
+    pub heading: String,
+    /// Markdown.
+    #[serde(default)]
+    pub body: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Robots {
+    /// Path prefixes crawlers are asked to skip.
+    #[serde(default)]
+    pub disallow: Vec<String>,
+}
+
+impl Site {
+    pub fn load() -> Self {
+        Self::parse(include_str!("../../site.toml"))
+            .unwrap_or_else(|err| panic!("site.toml is invalid: {}", err))
+    }
+
+    pub fn parse(source: &str) -> Result<Self, toml::de::Error> {
+        let mut site: Self = toml::from_str(source)?;
+        site.posts.per_page = site.posts.per_page.max(1);
+        site.base_url
+            .truncate(site.base_url.trim_end_matches('/').len());
+        Ok(site)
+    }
+
+    /// Absolute URL for a root-relative path such as `/post/bloom`.
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+}
+
+impl Link {
+    pub fn is_external(&self) -> bool {
+        self.href.starts_with("http://") || self.href.starts_with("https://")
+    }
+}
+
+fn default_avatar() -> String {
+    "/img/avatar.png".to_string()
+}
+
+fn default_latest() -> usize {
+    5
+}
+
+fn default_per_page() -> usize {
+    10
+}
+
+/// Renders the short markdown snippets in `site.toml` with pulldown-cmark's stock HTML
+/// output; they are styled by the base stylesheet like the rest of the page chrome.
+pub fn markdown(source: &str) -> Markup {
+    let mut output = String::new();
+    html::push_html(&mut output, Parser::new(source));
+    PreEscaped(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_bundled_site_config() {
+        let site = Site::load();
+
+        assert!(!site.title.is_empty());
+        assert!(site.base_url.starts_with("https://"));
+        assert!(site.nav.iter().any(|link| link.href == "/"));
+    }
+
+    #[test]
+    fn normalizes_base_url_and_fills_defaults() {
+        let site = Site::parse(
+            r#"
+            title = "Hut"
+            author = "Someone"
+            base_url = "https://example.com/"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(site.url("/feed.xml"), "https://example.com/feed.xml");
+        assert_eq!(site.avatar, "/img/avatar.png");
+        assert!(site.nav.is_empty());
+        assert_eq!(site.home.latest, 5);
+        assert_eq!(site.posts.per_page, 10);
+    }
+
+    #[test]
+    fn keeps_at_least_one_post_per_page() {
+        let site = Site::parse(
+            r#"
+            title = "Hut"
+            author = "Someone"
+            base_url = "https://example.com"
+
+            [posts]
+            per_page = 0
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(site.posts.per_page, 1);
+    }
+}